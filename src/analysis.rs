@@ -0,0 +1,847 @@
+use std::convert::TryFrom;
+
+use crate::attacks::{
+    attackers_of, attackers_with_occupancy, piece_attacks, sliding_attacks, AttackInfo,
+};
+use crate::board::{distance, pawn_attacks, BitBoard, Board, Direction, Square};
+use crate::movegen::{Move, MoveType};
+use crate::types::*;
+
+/// Phase weight of the full starting material
+pub const MAX_PHASE: u32 = 24;
+
+/// Phase at or below which the game counts as an endgame
+const ENDGAME_PHASE: u32 = 8;
+/// Phase at or above which an early game still counts as the opening
+const OPENING_PHASE: u32 = 20;
+/// Last fullmove number that can count as the opening
+const OPENING_MOVES: u32 = 12;
+
+/// Weight of each attacking piece type's closeness to the enemy king
+const TROPISM_WEIGHTS: [(PieceType, u32); 4] = [
+    (PieceType::Knight, 3),
+    (PieceType::Bishop, 2),
+    (PieceType::Rook, 2),
+    (PieceType::Queen, 5),
+];
+
+/// Broad stages of a game
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GamePhase {
+    Opening,
+    Middlegame,
+    Endgame,
+}
+
+/// Tactical patterns found on a board
+#[derive(Debug, Clone, PartialEq)]
+pub enum Motif {
+    /// One piece threatens several enemy pieces at once
+    Fork {
+        attacker: Square,
+        targets: Vec<Square>,
+    },
+    /// A slider attacks a piece shielding a more valuable piece behind it
+    ///
+    /// The pin is absolute when the piece behind is the king.
+    Pin {
+        pinner: Square,
+        pinned: Square,
+        target: Square,
+        absolute: bool,
+    },
+    /// A slider attacks a piece which exposes a less valuable piece behind
+    /// it when it moves away
+    Skewer {
+        attacker: Square,
+        front: Square,
+        back: Square,
+    },
+    /// Moving the blocker off the slider's line uncovers a threat on the
+    /// target
+    DiscoveredAttack {
+        slider: Square,
+        blocker: Square,
+        target: Square,
+    },
+    /// An enemy piece is attacked and not defended
+    HangingPiece { square: Square },
+}
+
+/// A piece that loses material if it is left where it is
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnPrise {
+    pub square: Square,
+    /// Material lost to the best capture sequence, in pawns
+    pub loss: i32,
+}
+
+/// Finds every motif that a side can exploit
+pub fn find_motifs(board: &Board, side: Side) -> Vec<Motif> {
+    let mut motifs = forks(board, side);
+    motifs.append(&mut pins(board, side));
+    motifs.append(&mut skewers(board, side));
+    motifs.append(&mut discovered_attacks(board, side));
+    motifs.append(&mut hanging_pieces(board, side));
+    motifs
+}
+
+/// Pieces of a side that threaten two or more enemy pieces
+pub fn forks(board: &Board, side: Side) -> Vec<Motif> {
+    let enemies = board.occupancy(side.opposite());
+    let mut motifs = Vec::new();
+
+    for attacker in board.occupancy(side).squares() {
+        let piece = board.piece_on(attacker).unwrap();
+        let attacks = piece_attacks(piece, BitBoard::from(attacker), board.occupied());
+        let targets: Vec<Square> = (attacks & enemies)
+            .squares()
+            .filter(|&target| threatens(board, piece, target))
+            .collect();
+
+        if targets.len() > 1 {
+            motifs.push(Motif::Fork { attacker, targets });
+        }
+    }
+
+    motifs
+}
+
+/// Enemy pieces pinned by the sliders of a side
+pub fn pins(board: &Board, side: Side) -> Vec<Motif> {
+    let enemies = board.occupancy(side.opposite());
+
+    lines(board, side)
+        .into_iter()
+        .filter(|&(_, front, back)| on(enemies, front) && on(enemies, back))
+        .filter(|&(_, front, back)| value_on(board, back) > value_on(board, front))
+        .map(|(pinner, pinned, target)| Motif::Pin {
+            pinner,
+            pinned,
+            target,
+            absolute: board.piece_on(target).unwrap().ptype == PieceType::King,
+        })
+        .collect()
+}
+
+/// Enemy pieces skewered by the sliders of a side
+pub fn skewers(board: &Board, side: Side) -> Vec<Motif> {
+    let enemies = board.occupancy(side.opposite());
+
+    lines(board, side)
+        .into_iter()
+        .filter(|&(_, front, back)| on(enemies, front) && on(enemies, back))
+        .filter(|&(_, front, back)| value_on(board, front) > value_on(board, back))
+        .map(|(attacker, front, back)| Motif::Skewer {
+            attacker,
+            front,
+            back,
+        })
+        .collect()
+}
+
+/// Threats a side could uncover by moving one of its own pieces off a
+/// slider's line
+pub fn discovered_attacks(board: &Board, side: Side) -> Vec<Motif> {
+    let own = board.occupancy(side);
+    let enemies = board.occupancy(side.opposite());
+
+    lines(board, side)
+        .into_iter()
+        .filter(|&(_, front, back)| on(own, front) && on(enemies, back))
+        .filter(|&(slider, _, back)| threatens(board, board.piece_on(slider).unwrap(), back))
+        .map(|(slider, blocker, target)| Motif::DiscoveredAttack {
+            slider,
+            blocker,
+            target,
+        })
+        .collect()
+}
+
+/// Enemy pieces attacked by a side and not defended
+///
+/// The enemy king is never reported as hanging.
+pub fn hanging_pieces(board: &Board, side: Side) -> Vec<Motif> {
+    let enemy = side.opposite();
+    let enemy_king = board.bit_board_for(Piece {
+        ptype: PieceType::King,
+        side: enemy,
+    });
+    let attacks = AttackInfo::new(board);
+    let hanging =
+        board.occupancy(enemy) & !enemy_king & attacks.attacks(side) & !attacks.attacks(enemy);
+
+    hanging
+        .squares()
+        .map(|square| Motif::HangingPiece { square })
+        .collect()
+}
+
+/// Pieces of a side that the opponent wins material by capturing
+///
+/// Unlike `hanging_pieces`, defended pieces are included when they are
+/// attacked more often than defended or by cheaper pieces.
+pub fn en_prise(board: &Board, side: Side) -> Vec<EnPrise> {
+    let king = board.bit_board_for(Piece {
+        ptype: PieceType::King,
+        side,
+    });
+
+    (board.occupancy(side) ^ king)
+        .squares()
+        .map(|square| EnPrise {
+            square,
+            loss: static_exchange(board, square, side.opposite()),
+        })
+        .filter(|en_prise| en_prise.loss > 0)
+        .collect()
+}
+
+/// Material a side wins by starting an exchange of captures on a square,
+/// in pawns
+///
+/// Each capture is made with the least valuable attacker, and either side
+/// may stop capturing when continuing would lose material. Returns 0 when
+/// the side is better off not capturing at all.
+pub fn static_exchange(board: &Board, square: Square, side: Side) -> i32 {
+    let mut occupied = board.occupied();
+    let mut capturing_side = side;
+    let mut on_square = value_on(board, square) as i32;
+    let mut captures = Vec::new();
+
+    while let Some(attacker) = least_valuable_attacker(board, square, capturing_side, occupied) {
+        captures.push(on_square);
+        on_square = value_on(board, attacker) as i32;
+        occupied = occupied ^ BitBoard::from(attacker);
+        capturing_side = capturing_side.opposite();
+    }
+
+    captures
+        .iter()
+        .rev()
+        .fold(0, |reply, &captured| (captured - reply).max(0))
+}
+
+fn least_valuable_attacker(
+    board: &Board,
+    square: Square,
+    side: Side,
+    occupied: BitBoard,
+) -> Option<Square> {
+    attackers_with_occupancy(board, square, side, occupied)
+        .squares()
+        .min_by_key(|&attacker| value_on(board, attacker))
+}
+
+/// Short plain-English explanation of what a move does, such as
+/// "develops the knight, forks the rook and queen"
+///
+/// Built from the motifs the move creates for the side making it, whether
+/// it develops a minor piece, and whether the moved piece ends up en prise
+/// or escapes being en prise. The explanation is empty when none of these
+/// apply. Returns `None` for moves that `Move::apply` cannot yet play, and
+/// for moves whose piece is not on the origin square or whose target square
+/// is occupied.
+pub fn explain_move(board: &Board, mv: &Move) -> Option<String> {
+    match mv.move_type() {
+        MoveType::Quiet | MoveType::DoublePawnPush => (),
+        _ => return None,
+    }
+    let piece = mv.piece();
+    let side = piece.side;
+    let origin = Square::try_from(mv.origin()).ok()?;
+    let target = Square::try_from(mv.target()).ok()?;
+    if board.piece_on(origin) != Some(piece) || board.piece_on(target).is_some() {
+        return None;
+    }
+
+    let mut after = *board;
+    mv.apply(&mut after).ok()?;
+    let name = |board: &Board, square| board.piece_on(square).map(|p| piece_name(p.ptype));
+    let mut phrases = Vec::new();
+
+    if (piece.ptype == PieceType::Knight || piece.ptype == PieceType::Bishop)
+        && side.relative_rank(origin.rank_index()) == 0
+    {
+        phrases.push(format!("develops the {}", piece_name(piece.ptype)));
+    }
+
+    let before = find_motifs(board, side);
+    for motif in find_motifs(&after, side) {
+        if before.contains(&motif) {
+            continue;
+        }
+
+        phrases.push(match motif {
+            Motif::Fork { targets, .. } => {
+                let names = targets
+                    .iter()
+                    .map(|&t| name(&after, t))
+                    .collect::<Option<Vec<&str>>>()?;
+                format!("forks the {}", list(&names))
+            }
+            Motif::Pin { pinned, target, .. } => format!(
+                "pins the {} to the {}",
+                name(&after, pinned)?,
+                name(&after, target)?
+            ),
+            Motif::Skewer { front, back, .. } => format!(
+                "skewers the {} and {}",
+                name(&after, front)?,
+                name(&after, back)?
+            ),
+            Motif::DiscoveredAttack { target, .. } => {
+                format!(
+                    "sets up a discovered attack on the {}",
+                    name(&after, target)?
+                )
+            }
+            Motif::HangingPiece { square } => {
+                format!("attacks the undefended {}", name(&after, square)?)
+            }
+        });
+    }
+
+    let was_en_prise = en_prise(board, side).iter().any(|e| e.square == origin);
+    let is_en_prise = en_prise(&after, side).iter().any(|e| e.square == target);
+    if is_en_prise {
+        phrases.push(format!("leaves the {} en prise", piece_name(piece.ptype)));
+    } else if was_en_prise {
+        phrases.push(format!(
+            "moves the {} out of danger",
+            piece_name(piece.ptype)
+        ));
+    }
+
+    Some(phrases.join(", "))
+}
+
+fn piece_name(ptype: PieceType) -> &'static str {
+    match ptype {
+        PieceType::Pawn => "pawn",
+        PieceType::Knight => "knight",
+        PieceType::Bishop => "bishop",
+        PieceType::Rook => "rook",
+        PieceType::Queen => "queen",
+        PieceType::King => "king",
+    }
+}
+
+/// Joins words as "a", "a and b" or "a, b and c"
+fn list(words: &[&str]) -> String {
+    match words.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        Some((last, _)) => last.to_string(),
+        None => String::new(),
+    }
+}
+
+/// Game phase from the non-pawn material on the board
+///
+/// Knights and bishops weigh 1, rooks 2 and queens 4, so the phase runs
+/// from `MAX_PHASE` in the starting position down to 0 with only kings and
+/// pawns left. Extra material from promotions is capped at `MAX_PHASE`.
+pub fn phase(board: &Board) -> u32 {
+    let weight = |ptype| match ptype {
+        PieceType::Knight | PieceType::Bishop => 1,
+        PieceType::Rook => 2,
+        PieceType::Queen => 4,
+        _ => 0,
+    };
+
+    let phase: u32 = Side::ALL
+        .iter()
+        .flat_map(|&side| {
+            PieceType::ALL
+                .iter()
+                .map(move |&ptype| Piece { ptype, side })
+        })
+        .map(|piece| board.bit_board_for(piece).count() * weight(piece.ptype))
+        .sum();
+
+    phase.min(MAX_PHASE)
+}
+
+/// Classifies the stage of a game from its material and fullmove number
+///
+/// The opening ends after move 12, or earlier once more than a queen's
+/// worth of phase has been traded. The endgame starts once the phase
+/// drops to 8, such as a rook and two minor pieces per side, or a queen
+/// each.
+pub fn game_phase(board: &Board, fullmove_number: u32) -> GamePhase {
+    let phase = phase(board);
+
+    if phase <= ENDGAME_PHASE {
+        GamePhase::Endgame
+    } else if fullmove_number <= OPENING_MOVES && phase >= OPENING_PHASE {
+        GamePhase::Opening
+    } else {
+        GamePhase::Middlegame
+    }
+}
+
+/// Squares that count towards a side's piece mobility
+///
+/// Excludes the side's own king and queens, its pawns that cannot push
+/// because the square ahead is occupied, and squares attacked by enemy
+/// pawns.
+pub fn mobility_area(board: &Board, side: Side) -> BitBoard {
+    let own = |ptype| board.bit_board_for(Piece { ptype, side });
+    let enemy_pawns = board.bit_board_for(Piece {
+        ptype: PieceType::Pawn,
+        side: side.opposite(),
+    });
+    let blocked_pawns =
+        own(PieceType::Pawn) & board.occupied().shift(Direction::forward(side.opposite()));
+
+    !(own(PieceType::King)
+        | own(PieceType::Queen)
+        | blocked_pawns
+        | pawn_attacks(enemy_pawns, side.opposite()))
+}
+
+/// Number of squares in a side's mobility area attacked by each of its
+/// knights, bishops, rooks and queens, summed
+pub fn mobility(board: &Board, side: Side) -> u32 {
+    let area = mobility_area(board, side);
+
+    [
+        PieceType::Knight,
+        PieceType::Bishop,
+        PieceType::Rook,
+        PieceType::Queen,
+    ]
+    .iter()
+    .map(|&ptype| Piece { ptype, side })
+    .flat_map(|piece| {
+        board
+            .bit_board_for(piece)
+            .squares()
+            .map(move |square| (piece, square))
+    })
+    .map(|(piece, square)| {
+        (piece_attacks(piece, BitBoard::from(square), board.occupied()) & area).count()
+    })
+    .sum()
+}
+
+/// How closely a side's knights, bishops, rooks and queens gather around
+/// the enemy king
+///
+/// Each piece scores its weight times seven less its distance to the king,
+/// so a piece seven king moves away counts for nothing. Cheaper than full
+/// king safety, which looks at attacks.
+pub fn king_tropism(board: &Board, side: Side) -> u32 {
+    let king = board
+        .bit_board_for(Piece {
+            ptype: PieceType::King,
+            side: side.opposite(),
+        })
+        .squares()
+        .next();
+    let king = match king {
+        Some(king) => king,
+        None => return 0,
+    };
+
+    TROPISM_WEIGHTS
+        .iter()
+        .flat_map(|&(ptype, weight)| {
+            board
+                .bit_board_for(Piece { ptype, side })
+                .squares()
+                .map(move |square| weight * (7 - distance(square, king) as u32))
+        })
+        .sum()
+}
+
+/// The first two pieces of either side along each line from the sliders
+/// of a side, as `(slider, front, back)`
+fn lines(board: &Board, side: Side) -> Vec<(Square, Square, Square)> {
+    let occupied = board.occupied();
+    let mut lines = Vec::new();
+
+    for slider in board.occupancy(side).squares() {
+        let ptype = board.piece_on(slider).unwrap().ptype;
+        for &direction in Direction::ALL.iter() {
+            let slides = match ptype {
+                PieceType::Bishop => direction.is_diagonal(),
+                PieceType::Rook => !direction.is_diagonal(),
+                PieceType::Queen => true,
+                _ => false,
+            };
+            if !slides {
+                continue;
+            }
+
+            let front = sliding_attacks(BitBoard::from(slider), occupied, direction) & occupied;
+            let back = sliding_attacks(front, occupied, direction) & occupied;
+            if let (Some(front), Some(back)) = (front.squares().next(), back.squares().next()) {
+                lines.push((slider, front, back));
+            }
+        }
+    }
+
+    lines
+}
+
+/// Whether attacking a target with a piece is a real threat, meaning the
+/// target is worth more than the attacker or is undefended
+fn threatens(board: &Board, attacker: Piece, target: Square) -> bool {
+    value_on(board, target) > piece_value(attacker.ptype)
+        || attackers_of(board, target, attacker.side.opposite()).0 == 0
+}
+
+fn on(bit_board: BitBoard, square: Square) -> bool {
+    (bit_board & BitBoard::from(square)).0 != 0
+}
+
+fn value_on(board: &Board, square: Square) -> u32 {
+    board
+        .piece_on(square)
+        .map_or(0, |piece| piece_value(piece.ptype))
+}
+
+/// Rough material value for comparing pieces, with the king above all
+fn piece_value(ptype: PieceType) -> u32 {
+    match ptype {
+        PieceType::Pawn => 1,
+        PieceType::Knight => 3,
+        PieceType::Bishop => 3,
+        PieceType::Rook => 5,
+        PieceType::Queen => 9,
+        PieceType::King => 1000,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::square;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_forks() {
+        let board = Board::from_ascii_diagram(
+            "r...k...
+             ..N.....
+             ........
+             ........
+             ........
+             ........
+             ........
+             ....K...",
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![Motif::Fork {
+                attacker: square("c7"),
+                targets: vec![square("a8"), square("e8")],
+            }],
+            forks(&board, Side::White)
+        );
+        assert!(forks(&board, Side::Black).is_empty());
+    }
+
+    #[test]
+    fn test_pins() {
+        let absolute = Board::from_ascii_diagram(
+            "....k...
+             ........
+             ..n.....
+             .B......
+             ........
+             ........
+             ........
+             ....K...",
+        )
+        .unwrap();
+        let relative = Board::from_ascii_diagram(
+            "....q..k
+             ........
+             ........
+             ....n...
+             ........
+             ........
+             ........
+             K...R...",
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![Motif::Pin {
+                pinner: square("b5"),
+                pinned: square("c6"),
+                target: square("e8"),
+                absolute: true,
+            }],
+            pins(&absolute, Side::White)
+        );
+        assert_eq!(
+            vec![Motif::Pin {
+                pinner: square("e1"),
+                pinned: square("e5"),
+                target: square("e8"),
+                absolute: false,
+            }],
+            pins(&relative, Side::White)
+        );
+        assert!(skewers(&relative, Side::White).is_empty());
+    }
+
+    #[test]
+    fn test_skewers() {
+        let board = Board::from_ascii_diagram(
+            "........
+             ........
+             ........
+             R...k..q
+             ........
+             ........
+             ........
+             K.......",
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![Motif::Skewer {
+                attacker: square("a5"),
+                front: square("e5"),
+                back: square("h5"),
+            }],
+            skewers(&board, Side::White)
+        );
+        assert!(pins(&board, Side::White).is_empty());
+    }
+
+    #[test]
+    fn test_discovered_attacks() {
+        let board = Board::from_ascii_diagram(
+            "....q..k
+             ........
+             ........
+             ........
+             ....N...
+             ........
+             ........
+             K...R...",
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![Motif::DiscoveredAttack {
+                slider: square("e1"),
+                blocker: square("e4"),
+                target: square("e8"),
+            }],
+            discovered_attacks(&board, Side::White)
+        );
+    }
+
+    #[test]
+    fn test_hanging_pieces() {
+        let board = Board::from_ascii_diagram(
+            "....k...
+             ........
+             ..p.....
+             ...n.n..
+             ....P...
+             ........
+             ........
+             ....K...",
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![Motif::HangingPiece {
+                square: square("f5")
+            }],
+            hanging_pieces(&board, Side::White)
+        );
+    }
+
+    #[test]
+    fn test_static_exchange() {
+        let board = Board::from_ascii_diagram(
+            "....r..k
+             ........
+             ....p...
+             ........
+             ........
+             ........
+             ....R...
+             K...R...",
+        )
+        .unwrap();
+
+        assert_eq!(1, static_exchange(&board, square("e6"), Side::White));
+        assert_eq!(0, static_exchange(&board, square("e2"), Side::Black));
+    }
+
+    #[test]
+    fn test_en_prise() {
+        let board = Board::from_ascii_diagram(
+            "....k...
+             ..p.....
+             ...b....
+             ...n.r.q
+             ....P.N.
+             ........
+             ........
+             ....K...",
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                EnPrise {
+                    square: square("d5"),
+                    loss: 2
+                },
+                EnPrise {
+                    square: square("f5"),
+                    loss: 4
+                },
+            ],
+            en_prise(&board, Side::Black)
+        );
+        assert!(en_prise(&Board::new(), Side::White).is_empty());
+    }
+
+    #[test]
+    fn test_phase() {
+        let rook_endgame = Board::from_ascii_diagram(
+            "....k...
+             ....r...
+             ........
+             ........
+             ........
+             ........
+             PPP.....
+             ....K..R",
+        )
+        .unwrap();
+
+        assert_eq!(MAX_PHASE, phase(&Board::new()));
+        assert_eq!(4, phase(&rook_endgame));
+        assert_eq!(GamePhase::Opening, game_phase(&Board::new(), 1));
+        assert_eq!(GamePhase::Middlegame, game_phase(&Board::new(), 20));
+        assert_eq!(GamePhase::Endgame, game_phase(&rook_endgame, 5));
+    }
+
+    #[test]
+    fn test_mobility_area() {
+        let board = Board::from_ascii_diagram(
+            "....k...
+             ........
+             ..p.....
+             ........
+             ....p...
+             ....P...
+             ...Q....
+             ....K...",
+        )
+        .unwrap();
+        let area = mobility_area(&board, Side::White);
+
+        for excluded in ["e1", "d2", "e3", "b5", "d5", "d3", "f3"].iter() {
+            assert_eq!(BitBoard(0), area & BitBoard::try_from(*excluded).unwrap());
+        }
+        assert_eq!(57, area.count());
+        assert_eq!(18, mobility(&Board::new(), Side::White));
+    }
+
+    #[test]
+    fn test_king_tropism() {
+        let board = Board::from_ascii_diagram(
+            "....k...
+             ........
+             ........
+             ..N.....
+             ...Q....
+             ........
+             ........
+             R...K...",
+        )
+        .unwrap();
+
+        // Knight three king moves away, queen four and rook seven
+        assert_eq!(3 * 4 + 5 * 3, king_tropism(&board, Side::White));
+        assert_eq!(0, king_tropism(&board, Side::Black));
+        assert_eq!(0, king_tropism(&Board::new(), Side::White));
+    }
+
+    #[test]
+    fn test_explain_move() {
+        let board = Board::from_ascii_diagram(
+            "....k...
+             ........
+             ...r.q..
+             ........
+             ........
+             ..N.....
+             ........
+             ....K...",
+        )
+        .unwrap();
+        let knight = Piece {
+            ptype: PieceType::Knight,
+            side: Side::White,
+        };
+
+        // The queen already attacks the knight on c3
+        assert_eq!(
+            Some("forks the rook and queen, moves the knight out of danger".to_string()),
+            explain_move(
+                &board,
+                &Move::new(knight, "c3", "e4", MoveType::Quiet).unwrap()
+            )
+        );
+        assert_eq!(
+            Some("leaves the knight en prise".to_string()),
+            explain_move(
+                &board,
+                &Move::new(knight, "c3", "d5", MoveType::Quiet).unwrap()
+            )
+        );
+        assert_eq!(
+            Some("develops the knight".to_string()),
+            explain_move(
+                &Board::new(),
+                &Move::new(knight, "g1", "f3", MoveType::Quiet).unwrap()
+            )
+        );
+        assert_eq!(
+            None,
+            explain_move(
+                &board,
+                &Move::new(knight, "c3", "d5", MoveType::Capture).unwrap()
+            )
+        );
+        // No knight on b1
+        assert_eq!(
+            None,
+            explain_move(
+                &board,
+                &Move::new(knight, "b1", "d2", MoveType::Quiet).unwrap()
+            )
+        );
+        // A quiet move onto the rook
+        assert_eq!(
+            None,
+            explain_move(
+                &board,
+                &Move::new(knight, "c3", "d6", MoveType::Quiet).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn test_no_motifs_in_starting_position() {
+        let board = Board::new();
+
+        assert!(find_motifs(&board, Side::White).is_empty());
+        assert!(find_motifs(&board, Side::Black).is_empty());
+    }
+}
@@ -0,0 +1,274 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use regex::Regex;
+
+use crate::types::InvalidAnnotationError;
+
+/// Numeric Annotation Glyph, written `$n` in PGN
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Nag(pub u8);
+
+/// Common move and position assessments
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Annotation {
+    /// `!`
+    Good,
+    /// `?`
+    Mistake,
+    /// `!!`
+    Brilliant,
+    /// `??`
+    Blunder,
+    /// `!?`
+    Interesting,
+    /// `?!`
+    Dubious,
+    /// `=`
+    Equal,
+    /// `∞`
+    Unclear,
+    /// `⩲`
+    WhiteSlightlyBetter,
+    /// `⩱`
+    BlackSlightlyBetter,
+    /// `±`
+    WhiteBetter,
+    /// `∓`
+    BlackBetter,
+    /// `+-`
+    WhiteWinning,
+    /// `-+`
+    BlackWinning,
+}
+
+/// Each annotation with its NAG number and suffix symbol
+const ANNOTATIONS: [(Annotation, u8, &str); 14] = [
+    (Annotation::Good, 1, "!"),
+    (Annotation::Mistake, 2, "?"),
+    (Annotation::Brilliant, 3, "!!"),
+    (Annotation::Blunder, 4, "??"),
+    (Annotation::Interesting, 5, "!?"),
+    (Annotation::Dubious, 6, "?!"),
+    (Annotation::Equal, 10, "="),
+    (Annotation::Unclear, 13, "∞"),
+    (Annotation::WhiteSlightlyBetter, 14, "⩲"),
+    (Annotation::BlackSlightlyBetter, 15, "⩱"),
+    (Annotation::WhiteBetter, 16, "±"),
+    (Annotation::BlackBetter, 17, "∓"),
+    (Annotation::WhiteWinning, 18, "+-"),
+    (Annotation::BlackWinning, 19, "-+"),
+];
+
+impl Annotation {
+    /// Parses a suffix symbol such as `!?` or `±`
+    pub fn from_suffix(suffix: &str) -> Result<Annotation, InvalidAnnotationError> {
+        ANNOTATIONS
+            .iter()
+            .find(|&&(_, _, symbol)| symbol == suffix)
+            .map(|&(annotation, _, _)| annotation)
+            .ok_or_else(|| InvalidAnnotationError {
+                msg: suffix.to_string(),
+            })
+    }
+
+    /// Symbol written after a move or position for this annotation
+    pub fn suffix(&self) -> &'static str {
+        self.entry().2
+    }
+
+    /// Whether the annotation judges a move rather than a position
+    pub fn is_move_assessment(&self) -> bool {
+        self.entry().1 <= 6
+    }
+
+    fn entry(&self) -> (Annotation, u8, &'static str) {
+        *ANNOTATIONS
+            .iter()
+            .find(|&&(annotation, _, _)| annotation == *self)
+            .unwrap()
+    }
+}
+
+impl fmt::Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.suffix())
+    }
+}
+
+impl From<Annotation> for Nag {
+    fn from(annotation: Annotation) -> Self {
+        Nag(annotation.entry().1)
+    }
+}
+
+impl TryFrom<Nag> for Annotation {
+    type Error = InvalidAnnotationError;
+
+    /// Maps a NAG to its annotation, failing for NAGs without a suffix
+    /// symbol
+    fn try_from(nag: Nag) -> Result<Self, Self::Error> {
+        ANNOTATIONS
+            .iter()
+            .find(|&&(_, number, _)| number == nag.0)
+            .map(|&(annotation, _, _)| annotation)
+            .ok_or_else(|| InvalidAnnotationError {
+                msg: nag.to_string(),
+            })
+    }
+}
+
+impl TryFrom<&str> for Nag {
+    type Error = InvalidAnnotationError;
+
+    /// Parses a PGN NAG such as `$14`, or a suffix symbol such as `!?`
+    fn try_from(nag: &str) -> Result<Self, Self::Error> {
+        match nag.strip_prefix('$') {
+            Some(number) => number.parse().map(Nag).map_err(|_| InvalidAnnotationError {
+                msg: nag.to_string(),
+            }),
+            None => Annotation::from_suffix(nag).map(Nag::from),
+        }
+    }
+}
+
+impl fmt::Display for Nag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "${}", self.0)
+    }
+}
+
+/// Clock time left after a move and time spent on it, as written in PGN
+/// comments with the `[%clk h:mm:ss]` and `[%emt h:mm:ss]` commands
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct MoveTimes {
+    pub clock: Option<Duration>,
+    pub elapsed: Option<Duration>,
+}
+
+impl MoveTimes {
+    /// Reads the time commands from a PGN comment, ignoring any other text
+    ///
+    /// A time command without its closing bracket is an error.
+    pub fn from_comment(comment: &str) -> Result<MoveTimes, InvalidAnnotationError> {
+        static COMMAND: OnceLock<Regex> = OnceLock::new();
+        let command = COMMAND.get_or_init(|| {
+            Regex::new(r"\[%(?P<name>clk|emt)\s+(?P<time>[^\[\]]*)(?P<close>\])?").unwrap()
+        });
+        let mut times = MoveTimes::default();
+
+        for captures in command.captures_iter(comment) {
+            if captures.name("close").is_none() {
+                return Err(InvalidAnnotationError {
+                    msg: captures[0].to_string(),
+                });
+            }
+            let time = Some(parse_time(captures["time"].trim())?);
+            match &captures["name"] {
+                "clk" => times.clock = time,
+                _ => times.elapsed = time,
+            }
+        }
+
+        Ok(times)
+    }
+}
+
+impl fmt::Display for MoveTimes {
+    /// Writes the time commands for a PGN comment, clock first
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let commands: Vec<String> = [("clk", self.clock), ("emt", self.elapsed)]
+            .iter()
+            .filter_map(|&(name, time)| {
+                time.map(|time| format!("[%{} {}]", name, format_time(time)))
+            })
+            .collect();
+
+        write!(f, "{}", commands.join(" "))
+    }
+}
+
+/// Parses `h:mm:ss`, with up to three decimal places on the seconds
+fn parse_time(time: &str) -> Result<Duration, InvalidAnnotationError> {
+    let error = || InvalidAnnotationError {
+        msg: time.to_string(),
+    };
+    static FORMAT: OnceLock<Regex> = OnceLock::new();
+    let format = FORMAT.get_or_init(|| {
+        Regex::new(r"^(?P<h>\d{1,6}):(?P<m>[0-5]\d):(?P<s>[0-5]\d)(\.(?P<frac>\d{1,3}))?$").unwrap()
+    });
+    let captures = format.captures(time).ok_or_else(error)?;
+
+    let hours: u64 = captures["h"].parse().unwrap();
+    let minutes: u64 = captures["m"].parse().unwrap();
+    let seconds: u64 = captures["s"].parse().unwrap();
+    let millis = captures
+        .name("frac")
+        .map_or(0, |frac| format!("{:0<3}", frac.as_str()).parse().unwrap());
+
+    Ok(Duration::from_secs(hours * 3600 + minutes * 60 + seconds) + Duration::from_millis(millis))
+}
+
+fn format_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+    let mut formatted = format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+
+    let millis = time.subsec_millis();
+    if millis != 0 {
+        formatted += format!(".{:03}", millis).trim_end_matches('0');
+    }
+
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suffixes() {
+        assert_eq!(Ok(Annotation::Interesting), Annotation::from_suffix("!?"));
+        assert_eq!(Ok(Annotation::WhiteBetter), Annotation::from_suffix("±"));
+        assert!(Annotation::from_suffix("!!!").is_err());
+        assert_eq!("??", Annotation::Blunder.to_string());
+        assert!(Annotation::Dubious.is_move_assessment());
+        assert!(!Annotation::Unclear.is_move_assessment());
+    }
+
+    #[test]
+    fn test_nags() {
+        assert_eq!(Nag(3), Nag::from(Annotation::Brilliant));
+        assert_eq!(Ok(Annotation::BlackWinning), Annotation::try_from(Nag(19)));
+        assert!(Annotation::try_from(Nag(22)).is_err());
+        assert_eq!(Ok(Nag(146)), Nag::try_from("$146"));
+        assert_eq!(Ok(Nag(2)), Nag::try_from("?"));
+        assert!(Nag::try_from("$x").is_err());
+        assert!(Nag::try_from("$256").is_err());
+        assert_eq!("$14", Nag(14).to_string());
+    }
+
+    #[test]
+    fn test_move_times() {
+        let times = MoveTimes::from_comment("Only move [%emt 0:00:07.5] [%clk 1:02:03]").unwrap();
+
+        assert_eq!(Some(Duration::from_secs(3723)), times.clock);
+        assert_eq!(Some(Duration::from_millis(7500)), times.elapsed);
+        assert_eq!("[%clk 1:02:03] [%emt 0:00:07.5]", times.to_string());
+        assert_eq!(
+            Ok(MoveTimes::default()),
+            MoveTimes::from_comment("Good move")
+        );
+        assert_eq!("", MoveTimes::default().to_string());
+        assert!(MoveTimes::from_comment("[%clk 0:75:00]").is_err());
+        assert!(MoveTimes::from_comment("[%emt soon]").is_err());
+        assert!(MoveTimes::from_comment("Time trouble [%clk 0:00:09").is_err());
+        assert!(MoveTimes::from_comment("[%clk 0:00:09 [%emt 0:00:01]").is_err());
+    }
+}
@@ -0,0 +1,208 @@
+/// Handle to a value stored in an `Arena`
+///
+/// Handles are only valid for the arena that issued them. A handle to a
+/// removed value stays invalid even after its slot is reused.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Index {
+    slot: usize,
+    generation: u32,
+}
+
+enum Slot<T> {
+    Occupied {
+        value: T,
+        generation: u32,
+    },
+    Vacant {
+        next_free: Option<usize>,
+        generation: u32,
+    },
+}
+
+/// Slab of values addressed by `Index` handles
+///
+/// Values live side by side in one vector, and removed slots are reused
+/// by later insertions, so building and pruning large trees of nodes
+/// neither fragments the heap nor chases pointers.
+pub struct Arena<T> {
+    slots: Vec<Slot<T>>,
+    free: Option<usize>,
+    len: usize,
+}
+
+impl<T> Arena<T> {
+    pub fn new() -> Arena<T> {
+        Arena::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Arena<T> {
+        Arena {
+            slots: Vec::with_capacity(capacity),
+            free: None,
+            len: 0,
+        }
+    }
+
+    /// Number of values in the arena
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Stores a value, reusing a free slot if there is one
+    pub fn insert(&mut self, value: T) -> Index {
+        self.len += 1;
+
+        match self.free {
+            Some(slot) => {
+                let generation = match self.slots[slot] {
+                    Slot::Vacant {
+                        next_free,
+                        generation,
+                    } => {
+                        self.free = next_free;
+                        generation
+                    }
+                    Slot::Occupied { .. } => unreachable!("free list points at a used slot"),
+                };
+                self.slots[slot] = Slot::Occupied { value, generation };
+
+                Index { slot, generation }
+            }
+            None => {
+                self.slots.push(Slot::Occupied {
+                    value,
+                    generation: 0,
+                });
+
+                Index {
+                    slot: self.slots.len() - 1,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    /// Takes a value out of the arena, freeing its slot
+    ///
+    /// Returns `None` if the handle was already removed.
+    pub fn remove(&mut self, index: Index) -> Option<T> {
+        self.get(index)?;
+
+        let vacant = Slot::Vacant {
+            next_free: self.free,
+            generation: index.generation.wrapping_add(1),
+        };
+        self.free = Some(index.slot);
+        self.len -= 1;
+
+        match std::mem::replace(&mut self.slots[index.slot], vacant) {
+            Slot::Occupied { value, .. } => Some(value),
+            Slot::Vacant { .. } => unreachable!(),
+        }
+    }
+
+    pub fn contains(&self, index: Index) -> bool {
+        self.get(index).is_some()
+    }
+
+    pub fn get(&self, index: Index) -> Option<&T> {
+        match self.slots.get(index.slot) {
+            Some(Slot::Occupied { value, generation }) if *generation == index.generation => {
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+        match self.slots.get_mut(index.slot) {
+            Some(Slot::Occupied { value, generation }) if *generation == index.generation => {
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Removes every value, keeping the allocated memory
+    pub fn clear(&mut self) {
+        let occupied: Vec<Index> = self.iter().map(|(index, _)| index).collect();
+        for index in occupied {
+            self.remove(index);
+        }
+    }
+
+    /// Values in the arena with their handles, in slot order
+    pub fn iter(&self) -> impl Iterator<Item = (Index, &T)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, entry)| match entry {
+                Slot::Occupied { value, generation } => Some((
+                    Index {
+                        slot,
+                        generation: *generation,
+                    },
+                    value,
+                )),
+                Slot::Vacant { .. } => None,
+            })
+    }
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_get() {
+        let mut arena = Arena::new();
+        let a = arena.insert("a");
+        let b = arena.insert("b");
+
+        *arena.get_mut(b).unwrap() = "c";
+
+        assert_eq!(2, arena.len());
+        assert_eq!(Some(&"a"), arena.get(a));
+        assert_eq!(Some(&"c"), arena.get(b));
+        assert_eq!(vec![(a, &"a"), (b, &"c")], arena.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_remove_reuses_slot() {
+        let mut arena = Arena::new();
+        let a = arena.insert(1);
+        arena.insert(2);
+
+        assert_eq!(Some(1), arena.remove(a));
+        assert_eq!(None, arena.remove(a));
+
+        let c = arena.insert(3);
+
+        assert_eq!(a.slot, c.slot);
+        assert!(!arena.contains(a));
+        assert_eq!(None, arena.get(a));
+        assert_eq!(Some(&3), arena.get(c));
+        assert_eq!(2, arena.len());
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut arena = Arena::new();
+        let a = arena.insert(1);
+
+        arena.clear();
+
+        assert!(arena.is_empty());
+        assert_eq!(None, arena.get(a));
+    }
+}
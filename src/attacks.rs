@@ -0,0 +1,326 @@
+use crate::board::{pawn_attacks, BitBoard, Board, Direction, Square};
+use crate::types::*;
+
+const ROOK_DIRECTIONS: [Direction; 4] = [
+    Direction::North,
+    Direction::East,
+    Direction::South,
+    Direction::West,
+];
+const BISHOP_DIRECTIONS: [Direction; 4] = [
+    Direction::NorthEast,
+    Direction::SouthEast,
+    Direction::SouthWest,
+    Direction::NorthWest,
+];
+const KNIGHT_STEPS: [(Direction, Direction); 8] = [
+    (Direction::North, Direction::NorthEast),
+    (Direction::North, Direction::NorthWest),
+    (Direction::East, Direction::NorthEast),
+    (Direction::East, Direction::SouthEast),
+    (Direction::South, Direction::SouthEast),
+    (Direction::South, Direction::SouthWest),
+    (Direction::West, Direction::SouthWest),
+    (Direction::West, Direction::NorthWest),
+];
+
+/// Squares attacked by the given knights
+pub fn knight_attacks(knights: BitBoard) -> BitBoard {
+    KNIGHT_STEPS
+        .iter()
+        .fold(BitBoard(0), |attacks, &(first, second)| {
+            attacks | knights.shift(first).shift(second)
+        })
+}
+
+/// Squares attacked by the given kings
+pub fn king_attacks(kings: BitBoard) -> BitBoard {
+    Direction::ALL
+        .iter()
+        .fold(BitBoard(0), |attacks, &direction| {
+            attacks | kings.shift(direction)
+        })
+}
+
+/// Squares attacked by sliders along one direction
+///
+/// Each ray stops at, and includes, the first occupied square.
+pub fn sliding_attacks(sliders: BitBoard, occupied: BitBoard, direction: Direction) -> BitBoard {
+    let mut attacks = BitBoard(0);
+    let mut ray = sliders.shift(direction);
+    while ray.0 != 0 {
+        attacks = attacks | ray;
+        ray = (ray & !occupied).shift(direction);
+    }
+
+    attacks
+}
+
+/// Squares attacked by the given rooks
+pub fn rook_attacks(rooks: BitBoard, occupied: BitBoard) -> BitBoard {
+    ROOK_DIRECTIONS
+        .iter()
+        .fold(BitBoard(0), |attacks, &direction| {
+            attacks | sliding_attacks(rooks, occupied, direction)
+        })
+}
+
+/// Squares attacked by the given bishops
+pub fn bishop_attacks(bishops: BitBoard, occupied: BitBoard) -> BitBoard {
+    BISHOP_DIRECTIONS
+        .iter()
+        .fold(BitBoard(0), |attacks, &direction| {
+            attacks | sliding_attacks(bishops, occupied, direction)
+        })
+}
+
+/// Squares attacked by pieces of one type and side
+pub fn piece_attacks(piece: Piece, pieces: BitBoard, occupied: BitBoard) -> BitBoard {
+    match piece.ptype {
+        PieceType::Pawn => pawn_attacks(pieces, piece.side),
+        PieceType::Knight => knight_attacks(pieces),
+        PieceType::Bishop => bishop_attacks(pieces, occupied),
+        PieceType::Rook => rook_attacks(pieces, occupied),
+        PieceType::Queen => rook_attacks(pieces, occupied) | bishop_attacks(pieces, occupied),
+        PieceType::King => king_attacks(pieces),
+    }
+}
+
+/// Squares of the pieces of one side that attack a square
+pub fn attackers_of(board: &Board, square: Square, side: Side) -> BitBoard {
+    attackers_with_occupancy(board, square, side, board.occupied())
+}
+
+/// Like `attackers_of`, but only pieces in `occupied` block or attack
+///
+/// Removing pieces from `occupied` reveals the sliders behind them, as
+/// when playing out an exchange.
+pub fn attackers_with_occupancy(
+    board: &Board,
+    square: Square,
+    side: Side,
+    occupied: BitBoard,
+) -> BitBoard {
+    let target = BitBoard::from(square);
+    let pieces = |ptype| board.bit_board_for(Piece { ptype, side }) & occupied;
+    let queens = pieces(PieceType::Queen);
+
+    (pawn_attacks(target, side.opposite()) & pieces(PieceType::Pawn))
+        | (knight_attacks(target) & pieces(PieceType::Knight))
+        | (bishop_attacks(target, occupied) & (pieces(PieceType::Bishop) | queens))
+        | (rook_attacks(target, occupied) & (pieces(PieceType::Rook) | queens))
+        | (king_attacks(target) & pieces(PieceType::King))
+}
+
+/// Squares attacked by each side, computed once for a board and shared by
+/// every term that needs them
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AttackInfo {
+    /// Attacks by side, white first, then by piece type from pawn to king
+    by_piece: [[BitBoard; 6]; 2],
+    all: [BitBoard; 2],
+    double: [BitBoard; 2],
+}
+
+impl AttackInfo {
+    pub fn new(board: &Board) -> AttackInfo {
+        let mut info = AttackInfo {
+            by_piece: [[BitBoard(0); 6]; 2],
+            all: [BitBoard(0); 2],
+            double: [BitBoard(0); 2],
+        };
+        let occupied = board.occupied();
+
+        for &side in Side::ALL.iter() {
+            for &ptype in PieceType::ALL.iter() {
+                let piece = Piece { ptype, side };
+                let pieces = board.bit_board_for(piece);
+                match ptype {
+                    // Split by capture direction so a square attacked by
+                    // two pawns counts as a double attack
+                    PieceType::Pawn => {
+                        let forward = pieces.shift(Direction::forward(side));
+                        info.add(piece, forward.shift(Direction::East));
+                        info.add(piece, forward.shift(Direction::West));
+                    }
+                    _ => {
+                        for square in pieces.squares() {
+                            info.add(
+                                piece,
+                                piece_attacks(piece, BitBoard::from(square), occupied),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        info
+    }
+
+    /// Records the attacks of one piece, or of one pawn capture direction
+    fn add(&mut self, piece: Piece, attacks: BitBoard) {
+        let side = piece.side.index();
+
+        self.double[side] = self.double[side] | (self.all[side] & attacks);
+        self.all[side] = self.all[side] | attacks;
+        self.by_piece[side][piece.ptype.index()] =
+            self.by_piece[side][piece.ptype.index()] | attacks;
+    }
+
+    /// Squares attacked by any piece of a side
+    pub fn attacks(&self, side: Side) -> BitBoard {
+        self.all[side.index()]
+    }
+
+    /// Squares attacked by pieces of one type and side
+    pub fn attacks_by(&self, piece: Piece) -> BitBoard {
+        self.by_piece[piece.side.index()][piece.ptype.index()]
+    }
+
+    /// Squares attacked by at least two pieces of a side
+    pub fn double_attacks(&self, side: Side) -> BitBoard {
+        self.double[side.index()]
+    }
+}
+
+/// Squares a rook attacks behind the first blocker on each rank or file
+///
+/// Only pieces in `blockers` are seen through, so passing one side's
+/// pieces finds the squares that side is pinning against or could
+/// discover an attack on.
+pub fn xray_rook_attacks(rook: BitBoard, occupied: BitBoard, blockers: BitBoard) -> BitBoard {
+    let attacks = rook_attacks(rook, occupied);
+    let blockers = blockers & attacks;
+
+    attacks ^ rook_attacks(rook, occupied ^ blockers)
+}
+
+/// Squares a bishop attacks behind the first blocker on each diagonal
+///
+/// See `xray_rook_attacks`.
+pub fn xray_bishop_attacks(bishop: BitBoard, occupied: BitBoard, blockers: BitBoard) -> BitBoard {
+    let attacks = bishop_attacks(bishop, occupied);
+    let blockers = blockers & attacks;
+
+    attacks ^ bishop_attacks(bishop, occupied ^ blockers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::consts::{RANK_2, RANK_3, RANK_6, RANK_7};
+    use crate::fixtures::squares;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_knight_attacks() {
+        assert_eq!(squares(&["b3", "c2"]), knight_attacks(squares(&["a1"])));
+        assert_eq!(
+            squares(&["f7", "h7", "e6", "e4", "f3", "h3"]),
+            knight_attacks(squares(&["g5"]))
+        );
+    }
+
+    #[test]
+    fn test_king_attacks() {
+        assert_eq!(squares(&["g8", "g7", "h7"]), king_attacks(squares(&["h8"])));
+        assert_eq!(8, king_attacks(squares(&["e4"])).count());
+    }
+
+    #[test]
+    fn test_attackers_of() {
+        let board = Board::from_ascii_diagram(
+            "....k...
+             ........
+             ...p....
+             ....N...
+             ........
+             ........
+             ....R...
+             ....K...",
+        )
+        .unwrap();
+        let e5 = Square::try_from("e5").unwrap();
+        let d6 = Square::try_from("d6").unwrap();
+        let e7 = Square::try_from("e7").unwrap();
+
+        assert_eq!(squares(&["d6"]), attackers_of(&board, e5, Side::Black));
+        assert_eq!(squares(&["e2"]), attackers_of(&board, e5, Side::White));
+        assert_eq!(BitBoard(0), attackers_of(&board, d6, Side::White));
+        assert_eq!(squares(&["e8"]), attackers_of(&board, e7, Side::Black));
+    }
+
+    #[test]
+    fn test_rook_attacks() {
+        let occupied = squares(&["a1", "a4", "c1"]);
+
+        assert_eq!(
+            squares(&["a2", "a3", "a4", "b1", "c1"]),
+            rook_attacks(squares(&["a1"]), occupied)
+        );
+        assert_eq!(
+            BitBoard(0x01010101010101fe),
+            rook_attacks(squares(&["a1"]), squares(&["a1"]))
+        );
+    }
+
+    #[test]
+    fn test_bishop_attacks() {
+        let occupied = squares(&["d4", "f6", "b2"]);
+
+        assert_eq!(
+            squares(&["e5", "f6", "c3", "b2", "c5", "b6", "a7", "e3", "f2", "g1"]),
+            bishop_attacks(squares(&["d4"]), occupied)
+        );
+    }
+
+    #[test]
+    fn test_attack_info() {
+        let info = AttackInfo::new(&Board::new());
+        let white_knight = Piece {
+            ptype: PieceType::Knight,
+            side: Side::White,
+        };
+
+        assert_eq!(
+            squares(&["a3", "c3", "d2", "e2", "f3", "h3"]),
+            info.attacks_by(white_knight)
+        );
+        assert_eq!(RANK_3, info.attacks(Side::White) & RANK_3);
+        assert_eq!(RANK_3, info.double_attacks(Side::White) & RANK_3);
+        assert_eq!(
+            squares(&["d2", "e2"]),
+            info.double_attacks(Side::White) & RANK_2
+        );
+        assert_eq!(RANK_6, info.attacks(Side::Black) & RANK_6);
+        assert_eq!(
+            squares(&["d7", "e7"]),
+            info.double_attacks(Side::Black) & RANK_7
+        );
+    }
+
+    #[test]
+    fn test_xray_rook_attacks() {
+        let occupied = squares(&["e1", "e4", "e8", "h1"]);
+
+        assert_eq!(
+            squares(&["e5", "e6", "e7", "e8"]),
+            xray_rook_attacks(squares(&["e1"]), occupied, squares(&["e4"]))
+        );
+        assert_eq!(
+            BitBoard(0),
+            xray_rook_attacks(squares(&["e1"]), occupied, BitBoard(0))
+        );
+    }
+
+    #[test]
+    fn test_xray_bishop_attacks() {
+        let occupied = squares(&["b2", "d4", "g7"]);
+
+        assert_eq!(
+            squares(&["e5", "f6", "g7"]),
+            xray_bishop_attacks(squares(&["b2"]), occupied, occupied)
+        );
+    }
+}
@@ -1,40 +1,229 @@
+pub mod consts;
+
 use std::convert::TryFrom;
 use std::fmt;
-use std::ops::BitXor;
+use std::ops::{BitAnd, BitOr, BitXor, Not};
 use std::str::FromStr;
+use std::sync::OnceLock;
 
 use regex::Regex;
 
+use self::consts::{FILE_A, FILE_H, RANK_3, RANK_6};
+use crate::attacks::attackers_of;
 use crate::types::*;
 
 const WHITE_PAWN_START_POS: u64 = 0x000000000000ff00;
 const WHITE_KNIGHT_START_POS: u64 = 0x0000000000000042;
 const WHITE_BISHOP_START_POS: u64 = 0x0000000000000024;
 const WHITE_ROOK_START_POS: u64 = 0x0000000000000081;
-const WHITE_QUEEN_START_POS: u64 = 0x0000000000000010;
-const WHITE_KING_START_POS: u64 = 0x0000000000000008;
+const WHITE_QUEEN_START_POS: u64 = 0x0000000000000008;
+const WHITE_KING_START_POS: u64 = 0x0000000000000010;
 const BLACK_PAWN_START_POS: u64 = 0x00ff000000000000;
 const BLACK_KNIGHT_START_POS: u64 = 0x4200000000000000;
 const BLACK_BISHOP_START_POS: u64 = 0x2400000000000000;
 const BLACK_ROOK_START_POS: u64 = 0x8100000000000000;
-const BLACK_QUEEN_START_POS: u64 = 0x1000000000000000;
-const BLACK_KING_START_POS: u64 = 0x0800000000000000;
+const BLACK_QUEEN_START_POS: u64 = 0x0800000000000000;
+const BLACK_KING_START_POS: u64 = 0x1000000000000000;
+
+const DISTANCES: [[u8; 64]; 64] = distance_table(false);
+const MANHATTAN_DISTANCES: [[u8; 64]; 64] = distance_table(true);
+
+/// Compass directions on the board, with north towards rank 8
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    /// All directions, clockwise from north
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// Whether the direction runs along a diagonal
+    pub fn is_diagonal(&self) -> bool {
+        matches!(
+            self,
+            Direction::NorthEast
+                | Direction::SouthEast
+                | Direction::SouthWest
+                | Direction::NorthWest
+        )
+    }
+
+    /// The direction pawns of a side move in
+    pub fn forward(side: Side) -> Direction {
+        match side {
+            Side::White => Direction::North,
+            Side::Black => Direction::South,
+        }
+    }
+}
+
+/// Colours of the squares on the board
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SquareColor {
+    Light,
+    Dark,
+}
+
+/// A square on the board, stored as its little-endian rank-file index
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Square(u8);
+
+impl Square {
+    /// Creates a square from its little-endian rank-file index
+    pub fn from_index(index: u8) -> Result<Square, InvalidSquareError> {
+        if index > 63 {
+            return Err(InvalidSquareError {
+                msg: index.to_string(),
+            });
+        }
+
+        Ok(Square(index))
+    }
+
+    /// Little-endian rank-file index of the square
+    pub fn index(self) -> u8 {
+        self.0
+    }
+
+    /// Index of the square's file, from 0 for a to 7 for h
+    pub fn file_index(self) -> u8 {
+        self.0 % 8
+    }
+
+    /// Index of the square's rank, from 0 for rank 1 to 7 for rank 8
+    pub fn rank_index(self) -> u8 {
+        self.0 / 8
+    }
+
+    /// Colour of the square, a1 being dark
+    pub fn color(self) -> SquareColor {
+        if (self.file_index() + self.rank_index()) & 1 == 0 {
+            SquareColor::Dark
+        } else {
+            SquareColor::Light
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Square {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Square(u.int_in_range(0..=63)?))
+    }
+}
+
+impl TryFrom<&str> for Square {
+    type Error = InvalidSquareError;
+
+    fn try_from(square: &str) -> Result<Self, Self::Error> {
+        Ok(Square(lerf_index_for(square)?))
+    }
+}
+
+impl FromStr for Square {
+    type Err = InvalidSquareError;
+
+    fn from_str(square: &str) -> Result<Self, Self::Err> {
+        Square::try_from(square)
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            file_for_index(self.file_index()).unwrap(),
+            self.rank_index() + 1
+        )
+    }
+}
+
+/// Whether two squares have the same colour
+pub fn same_color_squares(a: Square, b: Square) -> bool {
+    a.color() == b.color()
+}
+
+/// Number of king moves between two squares
+pub fn distance(a: Square, b: Square) -> u8 {
+    DISTANCES[a.index() as usize][b.index() as usize]
+}
+
+/// Number of one-square rook moves between two squares
+pub fn manhattan_distance(a: Square, b: Square) -> u8 {
+    MANHATTAN_DISTANCES[a.index() as usize][b.index() as usize]
+}
+
+/// Chebyshev or Manhattan distances between every pair of squares
+const fn distance_table(manhattan: bool) -> [[u8; 64]; 64] {
+    let mut table = [[0; 64]; 64];
+    let mut a: i32 = 0;
+    while a < 64 {
+        let mut b: i32 = 0;
+        while b < 64 {
+            let file_distance = (a % 8 - b % 8).abs();
+            let rank_distance = (a / 8 - b / 8).abs();
+            table[a as usize][b as usize] = if manhattan {
+                file_distance + rank_distance
+            } else if file_distance > rank_distance {
+                file_distance
+            } else {
+                rank_distance
+            } as u8;
+            b += 1;
+        }
+        a += 1;
+    }
+
+    table
+}
 
 /// Little-endian rank-file bitboard
 ///
-/// ```
+/// ```text
 /// bitIndex = rankIndex * 8 + fileIndex
 /// ```
 ///
 #[derive(PartialEq, Copy, Clone)]
 pub struct BitBoard(pub u64);
 
-impl From<&str> for BitBoard {
+impl TryFrom<&str> for BitBoard {
+    type Error = InvalidSquareError;
+
     /// Maps a coordinate to a square on a bitboard
-    fn from(square: &str) -> Self {
-        let bit_index = lerf_index_for(square).unwrap();
+    fn try_from(square: &str) -> Result<Self, Self::Error> {
+        Ok(BitBoard(1u64 << lerf_index_for(square)?))
+    }
+}
 
-        BitBoard(1u64 << bit_index)
+impl FromStr for BitBoard {
+    type Err = InvalidSquareError;
+
+    fn from_str(square: &str) -> Result<Self, Self::Err> {
+        BitBoard::try_from(square)
+    }
+}
+
+impl From<Square> for BitBoard {
+    fn from(square: Square) -> Self {
+        BitBoard(1u64 << square.0)
     }
 }
 
@@ -46,6 +235,103 @@ impl BitXor for BitBoard {
     }
 }
 
+impl BitAnd for BitBoard {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        BitBoard(self.0 & rhs.0)
+    }
+}
+
+impl BitOr for BitBoard {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        BitBoard(self.0 | rhs.0)
+    }
+}
+
+impl Not for BitBoard {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        BitBoard(!self.0)
+    }
+}
+
+impl BitBoard {
+    /// Iterates over the squares on the bitboard, from a1 to h8
+    pub fn squares(self) -> BitBoardSquares {
+        BitBoardSquares(self.0)
+    }
+
+    /// Number of squares on the bitboard
+    pub fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Shifts every square one step in a direction
+    ///
+    /// Squares shifted off the edge of the board are dropped rather than
+    /// wrapping around to the opposite file.
+    pub fn shift(self, direction: Direction) -> BitBoard {
+        match direction {
+            Direction::North => BitBoard(self.0 << 8),
+            Direction::NorthEast => BitBoard((self.0 << 9) & !FILE_A.0),
+            Direction::East => BitBoard((self.0 << 1) & !FILE_A.0),
+            Direction::SouthEast => BitBoard((self.0 >> 7) & !FILE_A.0),
+            Direction::South => BitBoard(self.0 >> 8),
+            Direction::SouthWest => BitBoard((self.0 >> 9) & !FILE_H.0),
+            Direction::West => BitBoard((self.0 >> 1) & !FILE_H.0),
+            Direction::NorthWest => BitBoard((self.0 << 7) & !FILE_H.0),
+        }
+    }
+}
+
+/// Iterator over the squares on a bitboard
+pub struct BitBoardSquares(u64);
+
+impl Iterator for BitBoardSquares {
+    type Item = Square;
+
+    fn next(&mut self) -> Option<Square> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let index = self.0.trailing_zeros() as u8;
+        self.0 &= self.0 - 1;
+        Some(Square(index))
+    }
+}
+
+/// Squares reachable by a single push of the given pawns
+pub fn pawn_push_targets(pawns: BitBoard, empty: BitBoard, side: Side) -> BitBoard {
+    pawns.shift(Direction::forward(side)) & empty
+}
+
+/// Squares reachable by a double push of the given pawns
+///
+/// Only pawns on their starting rank with both squares ahead empty can
+/// double push.
+pub fn pawn_double_push_targets(pawns: BitBoard, empty: BitBoard, side: Side) -> BitBoard {
+    let single_pushes = pawn_push_targets(pawns, empty, side);
+    let third_rank = match side {
+        Side::White => RANK_3,
+        Side::Black => RANK_6,
+    };
+
+    pawn_push_targets(single_pushes & third_rank, empty, side)
+}
+
+/// Squares attacked by the given pawns
+pub fn pawn_attacks(pawns: BitBoard, side: Side) -> BitBoard {
+    match side {
+        Side::White => pawns.shift(Direction::NorthEast) | pawns.shift(Direction::NorthWest),
+        Side::Black => pawns.shift(Direction::SouthEast) | pawns.shift(Direction::SouthWest),
+    }
+}
+
 impl fmt::Debug for BitBoard {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let bits = self.0.reverse_bits();
@@ -63,21 +349,23 @@ impl fmt::Debug for BitBoard {
                  abcdefgh",
             (bits & 0x00000000000000ff),
             (bits & 0x000000000000ff00) >> 8,
-            (bits & 0x0000000000ff0000) >> 2 * 8,
-            (bits & 0x00000000ff000000) >> 3 * 8,
-            (bits & 0x000000ff00000000) >> 4 * 8,
-            (bits & 0x0000ff0000000000) >> 5 * 8,
-            (bits & 0x00ff000000000000) >> 6 * 8,
-            (bits & 0xff00000000000000) >> 7 * 8
+            (bits & 0x0000000000ff0000) >> (2 * 8),
+            (bits & 0x00000000ff000000) >> (3 * 8),
+            (bits & 0x000000ff00000000) >> (4 * 8),
+            (bits & 0x0000ff0000000000) >> (5 * 8),
+            (bits & 0x00ff000000000000) >> (6 * 8),
+            (bits & 0xff00000000000000) >> (7 * 8)
         )
     }
 }
 
 /// 8x8 board to store pieces by square
 ///
-/// Indexed by file then rank
-#[derive(Copy, Clone)]
-pub struct _8x8Board([[(Option<Piece>); 8]; 8]);
+/// Stored as one byte per square in LERF order, 0 for an empty square and
+/// otherwise 1 plus the piece's index in `PieceType::ALL`, offset by 6 for
+/// black pieces.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct _8x8Board([u8; 64]);
 
 impl _8x8Board {
     /// Creates a new 8x8 board with the default piece configuration
@@ -97,7 +385,84 @@ impl _8x8Board {
 
     /// Creates a new, empty 8x8 board
     pub fn empty() -> _8x8Board {
-        _8x8Board([[None; 8]; 8])
+        _8x8Board([0; 64])
+    }
+
+    /// Creates an 8x8 board from a text diagram
+    ///
+    /// Accepts the layout produced by `Display` as well as plain grids of
+    /// piece letters, with ranks listed from 8 down to 1 and empty squares
+    /// written as `.`. Rank labels, the file footer and whitespace between
+    /// squares are optional.
+    pub fn from_ascii_diagram(diagram: &str) -> Result<_8x8Board, InvalidDiagramError> {
+        Self::from_themed_diagram(diagram, &Theme::default())
+    }
+
+    /// Creates an 8x8 board from a text diagram drawn with a theme
+    ///
+    /// Reads the layouts `from_ascii_diagram` accepts, with the theme's
+    /// symbols taking precedence over piece glyphs and letters. This reads
+    /// back anything `render` draws with the same theme, as long as no
+    /// symbol contains whitespace or starts with a digit.
+    pub fn from_themed_diagram(
+        diagram: &str,
+        theme: &Theme,
+    ) -> Result<_8x8Board, InvalidDiagramError> {
+        let mut new_board = Self::empty();
+        let mut rank_count = 0;
+
+        for line in diagram.lines() {
+            let cells: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+            if cells.is_empty() || cells == "abcdefgh" {
+                continue;
+            }
+
+            if rank_count == 8 {
+                return Err(InvalidDiagramError {
+                    msg: format!("Too many ranks: {}", line),
+                });
+            }
+            let rank_index = 7 - rank_count;
+
+            let mut rest = cells.as_str();
+            if let Some(label) = rest.chars().next().filter(char::is_ascii_digit) {
+                if label.to_digit(10) != Some(rank_index as u32 + 1) {
+                    return Err(InvalidDiagramError {
+                        msg: format!("Expected rank {}, found {}", rank_index + 1, label),
+                    });
+                }
+                rest = &rest[1..];
+            }
+
+            let mut rank = Vec::new();
+            while !rest.is_empty() {
+                let (piece, len) = theme
+                    .read_symbol(rest)
+                    .map_err(|e| InvalidDiagramError { msg: e.to_string() })?;
+                rank.push(piece);
+                rest = &rest[len..];
+            }
+
+            if rank.len() != 8 {
+                return Err(InvalidDiagramError {
+                    msg: format!("Expected 8 squares on rank {}: {}", rank_index + 1, line),
+                });
+            }
+
+            for (file_index, &piece) in rank.iter().enumerate() {
+                new_board.set(rank_index * 8 + file_index, piece);
+            }
+
+            rank_count += 1;
+        }
+
+        if rank_count != 8 {
+            return Err(InvalidDiagramError {
+                msg: format!("Expected 8 ranks, found {}", rank_count),
+            });
+        }
+
+        Ok(new_board)
     }
 
     /// Sets the value of a square on the board
@@ -106,30 +471,73 @@ impl _8x8Board {
         square: &str,
         value: Option<Piece>,
     ) -> Result<(), InvalidSquareError> {
-        let bit_index = lerf_index_for(square)? as usize;
-        self.0[bit_index / 8][bit_index % 8] = value;
+        self.set(lerf_index_for(square)? as usize, value);
         Ok(())
     }
 
     /// Gets the value of a square on the board
-    pub fn get_square(&self, square: &str) -> Option<Piece> {
-        let bit_index = lerf_index_for(square).unwrap() as usize;
-        self.0[bit_index / 8][bit_index % 8]
+    pub fn get_square(&self, square: &str) -> Result<Option<Piece>, InvalidSquareError> {
+        Ok(self.get(lerf_index_for(square)? as usize))
+    }
+
+    /// Draws the board as a text diagram with the given symbols, rank 8
+    /// first
+    pub fn render(&self, theme: &Theme) -> String {
+        let mut display_str = "".to_string();
+        for rank_index in (0..8).rev() {
+            display_str.push_str(&format!("{} ", rank_index + 1));
+            for file_index in 0..8 {
+                if let Some(piece) = self.get(rank_index * 8 + file_index) {
+                    display_str += theme.symbol(piece);
+                } else {
+                    display_str += &theme.empty;
+                }
+            }
+            display_str += "\n";
+        }
+
+        display_str += "\n  abcdefgh";
+
+        display_str
+    }
+
+    fn get(&self, index: usize) -> Option<Piece> {
+        match self.0[index] {
+            0 => None,
+            code => Some(Piece {
+                ptype: PieceType::ALL[(code as usize - 1) % 6],
+                side: if code > 6 { Side::Black } else { Side::White },
+            }),
+        }
+    }
+
+    fn set(&mut self, index: usize, value: Option<Piece>) {
+        self.0[index] = value.map_or(0, |piece| {
+            1 + (piece.side.index() * 6 + piece.ptype.index()) as u8
+        });
+    }
+}
+
+impl Default for _8x8Board {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl TryFrom<[[&str; 8]; 8]> for _8x8Board {
     type Error = InvalidPieceError;
 
+    /// Creates an 8x8 board from piece glyphs or FEN letters, rank 8 first
     fn try_from(board: [[&str; 8]; 8]) -> Result<Self, Self::Error> {
         let mut new_board = Self::empty();
 
         for (rank_index, rank) in board.iter().enumerate() {
             for (file_index, piece) in rank.iter().enumerate() {
-                new_board.0[7 - rank_index][file_index] = match piece {
+                let piece = match piece {
                     &"" => None,
                     _ => Some(Piece::try_from(board[rank_index][file_index])?),
-                }
+                };
+                new_board.set((7 - rank_index) * 8 + file_index, piece);
             }
         }
 
@@ -139,25 +547,114 @@ impl TryFrom<[[&str; 8]; 8]> for _8x8Board {
 
 impl fmt::Display for _8x8Board {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut display_str = "".to_string();
-        for (rank_index, rank) in self.0.iter().enumerate() {
-            display_str.push_str(&format!("{} ", 8 - rank_index));
-            for (_file_index, piece) in rank.iter().enumerate() {
-                if let Some(piece) = piece {
-                    display_str += &piece.to_string();
-                } else {
-                    display_str += &".".to_string();
-                }
+        write!(f, "{}", self.render(&Theme::default()))
+    }
+}
+
+/// Symbols used to draw pieces and empty squares in text diagrams
+///
+/// The default theme uses the Unicode chess glyphs, which some terminals
+/// and chat platforms draw poorly or with the colours reversed. Any
+/// strings can be used, including emoji. `from_ascii_diagram` only reads
+/// diagrams drawn with the default and letter themes, so diagrams drawn
+/// with any other theme are read back with `from_themed_diagram`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// Symbols for white pieces, from pawn to king
+    pub white: [String; 6],
+    /// Symbols for black pieces, from pawn to king
+    pub black: [String; 6],
+    pub empty: String,
+}
+
+impl Theme {
+    pub fn new(white: [&str; 6], black: [&str; 6], empty: &str) -> Theme {
+        Theme {
+            white: white.map(String::from),
+            black: black.map(String::from),
+            empty: empty.to_string(),
+        }
+    }
+
+    /// Outline glyphs for white and filled glyphs for black
+    pub fn unicode() -> Theme {
+        Theme::new(
+            ["♙", "♘", "♗", "♖", "♕", "♔"],
+            ["♟", "♞", "♝", "♜", "♛", "♚"],
+            ".",
+        )
+    }
+
+    /// Filled glyphs for white and outline glyphs for black, which reads
+    /// better as light text on a dark background
+    pub fn inverted() -> Theme {
+        let unicode = Theme::unicode();
+
+        Theme {
+            white: unicode.black,
+            black: unicode.white,
+            empty: unicode.empty,
+        }
+    }
+
+    /// FEN letters, uppercase for white
+    pub fn letters() -> Theme {
+        Theme::new(
+            ["P", "N", "B", "R", "Q", "K"],
+            ["p", "n", "b", "r", "q", "k"],
+            ".",
+        )
+    }
+
+    /// Symbol for a piece
+    pub fn symbol(&self, piece: Piece) -> &str {
+        let index = piece.ptype.index();
+
+        match piece.side {
+            Side::White => &self.white[index],
+            Side::Black => &self.black[index],
+        }
+    }
+
+    /// Reads the symbol at the start of some text, giving the square's
+    /// contents and the symbol's length in bytes
+    ///
+    /// The longest matching symbol of the theme wins. Otherwise a single
+    /// piece glyph, FEN letter or `.` is read.
+    fn read_symbol(&self, text: &str) -> Result<(Option<Piece>, usize), InvalidPieceError> {
+        let mut symbols = vec![(None, self.empty.as_str())];
+        for &side in Side::ALL.iter() {
+            for &ptype in PieceType::ALL.iter() {
+                let piece = Piece { ptype, side };
+                symbols.push((Some(piece), self.symbol(piece)));
             }
-            display_str += &"\n".to_string();
         }
 
-        display_str += &"\n  abcdefgh".to_string();
+        let theme_match = symbols
+            .into_iter()
+            .filter(|(_, symbol)| !symbol.is_empty() && text.starts_with(symbol))
+            .max_by_key(|(_, symbol)| symbol.len());
+        if let Some((piece, symbol)) = theme_match {
+            return Ok((piece, symbol.len()));
+        }
+
+        let cell = text.chars().next().unwrap();
+        let piece = match cell {
+            '.' => None,
+            _ => Some(Piece::try_from(cell.to_string().as_str())?),
+        };
+
+        Ok((piece, cell.len_utf8()))
+    }
+}
 
-        write!(f, "{}", display_str)
+impl Default for Theme {
+    fn default() -> Self {
+        Self::unicode()
     }
 }
 
+#[derive(Copy, Clone)]
 pub struct PieceSet {
     pawns: BitBoard,
     knights: BitBoard,
@@ -169,6 +666,17 @@ pub struct PieceSet {
 
 /// Bitboards for all pieces of a color
 impl PieceSet {
+    fn empty() -> PieceSet {
+        PieceSet {
+            pawns: BitBoard(0),
+            knights: BitBoard(0),
+            bishops: BitBoard(0),
+            rooks: BitBoard(0),
+            queens: BitBoard(0),
+            king: BitBoard(0),
+        }
+    }
+
     fn new(side: Side) -> PieceSet {
         match side {
             Side::White => PieceSet {
@@ -190,6 +698,10 @@ impl PieceSet {
         }
     }
 
+    fn occupancy(&self) -> BitBoard {
+        self.pawns | self.knights | self.bishops | self.rooks | self.queens | self.king
+    }
+
     fn bit_board_for(&self, piece: PieceType) -> BitBoard {
         match piece {
             PieceType::Pawn => self.pawns,
@@ -226,19 +738,49 @@ impl PieceSet {
 }
 
 /// Bitboards for all pieces on the board
+///
+/// Small enough to copy cheaply, since the square array stores one byte
+/// per square.
+#[derive(Copy, Clone)]
 pub struct Board {
     white: PieceSet,
     black: PieceSet,
     squares: _8x8Board,
+    material: MaterialKey,
 }
 
 impl Board {
     pub fn new() -> Board {
-        Board {
+        let mut board = Board {
             white: PieceSet::new(Side::White),
             black: PieceSet::new(Side::Black),
             squares: _8x8Board::new(),
+            material: MaterialKey(0),
+        };
+        for &side in Side::ALL.iter() {
+            for &ptype in PieceType::ALL.iter() {
+                let piece = Piece { ptype, side };
+                board
+                    .material
+                    .set_count(piece, board.bit_board_for(piece).count());
+            }
         }
+
+        board
+    }
+
+    /// Creates a board from a text diagram
+    ///
+    /// See `_8x8Board::from_ascii_diagram` for the accepted layouts.
+    pub fn from_ascii_diagram(diagram: &str) -> Result<Board, InvalidDiagramError> {
+        Ok(Board::from(_8x8Board::from_ascii_diagram(diagram)?))
+    }
+
+    /// Creates a board from a text diagram drawn with a theme
+    ///
+    /// See `_8x8Board::from_themed_diagram`.
+    pub fn from_themed_diagram(diagram: &str, theme: &Theme) -> Result<Board, InvalidDiagramError> {
+        Ok(Board::from(_8x8Board::from_themed_diagram(diagram, theme)?))
     }
 
     pub fn bit_board_for(&self, piece: Piece) -> BitBoard {
@@ -257,12 +799,41 @@ impl Board {
                 self.black.set_bit_board(bit_board, piece.ptype);
             }
         }
+        self.material.set_count(piece, bit_board.count());
+    }
+
+    /// Material signature of the pieces on the board
+    pub fn material(&self) -> MaterialKey {
+        self.material
+    }
+
+    /// Squares occupied by pieces of one side
+    pub fn occupancy(&self, side: Side) -> BitBoard {
+        match side {
+            Side::White => self.white.occupancy(),
+            Side::Black => self.black.occupancy(),
+        }
     }
 
-    pub fn get_square(&self, square: &str) -> Option<Piece> {
+    /// Squares occupied by pieces of either side
+    pub fn occupied(&self) -> BitBoard {
+        self.white.occupancy() | self.black.occupancy()
+    }
+
+    pub fn get_square(&self, square: &str) -> Result<Option<Piece>, InvalidSquareError> {
         self.squares.get_square(square)
     }
 
+    /// Draws the board as a text diagram, see `_8x8Board::render`
+    pub fn render(&self, theme: &Theme) -> String {
+        self.squares.render(theme)
+    }
+
+    /// Gets the piece on a square, if any
+    pub fn piece_on(&self, square: Square) -> Option<Piece> {
+        self.squares.get(square.index() as usize)
+    }
+
     pub fn set_square(
         &mut self,
         square: &str,
@@ -270,27 +841,123 @@ impl Board {
     ) -> Result<(), InvalidSquareError> {
         self.squares.set_square(square, piece)
     }
+
+    /// The same position with the ranks mirrored and the colours of the
+    /// pieces swapped, so white's pieces stand where black's stood
+    pub fn color_flipped(&self) -> Board {
+        let mut squares = _8x8Board::empty();
+        for index in 0..64 {
+            let piece = self.squares.get(index).map(|piece| Piece {
+                ptype: piece.ptype,
+                side: piece.side.opposite(),
+            });
+            squares.set(index ^ 56, piece);
+        }
+
+        Board::from(squares)
+    }
+
+    /// Number of a side's pieces attacking each square, indexed like the
+    /// bits of a bitboard
+    ///
+    /// Squares holding the side's own pieces count their defenders.
+    pub fn attack_map(&self, side: Side) -> [u8; 64] {
+        let mut map = [0; 64];
+        for (index, count) in map.iter_mut().enumerate() {
+            let square = Square(index as u8);
+            *count = attackers_of(self, square, side).count() as u8;
+        }
+
+        map
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<_8x8Board> for Board {
+    /// Builds the piece bitboards from the squares of an 8x8 board
+    fn from(squares: _8x8Board) -> Self {
+        let mut board = Board {
+            white: PieceSet::empty(),
+            black: PieceSet::empty(),
+            squares,
+            material: MaterialKey(0),
+        };
+
+        for index in 0..64 {
+            if let Some(piece) = squares.get(index) {
+                let square_bb = BitBoard(1u64 << index);
+                board.set_bit_board(board.bit_board_for(piece) ^ square_bb, piece);
+            }
+        }
+
+        board
+    }
+}
+
+/// Number of pieces of each type for both sides
+///
+/// Counts are packed four bits per piece type and side, so equal
+/// material gives equal keys and a key can be used to look up
+/// material-specific evaluation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MaterialKey(u64);
+
+impl MaterialKey {
+    /// Number of pieces of a type and side
+    pub fn count(self, piece: Piece) -> u32 {
+        ((self.0 >> Self::shift_for(piece)) & 0xf) as u32
+    }
+
+    fn set_count(&mut self, piece: Piece, count: u32) {
+        let shift = Self::shift_for(piece);
+        self.0 = (self.0 & !(0xf << shift)) | ((count.min(0xf) as u64) << shift);
+    }
+
+    fn shift_for(piece: Piece) -> u32 {
+        (piece.side.index() * 6 + piece.ptype.index()) as u32 * 4
+    }
+}
+
+impl fmt::Display for MaterialKey {
+    /// Writes the signature with white's pieces first, e.g. `KRPvKR`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut signature = String::new();
+        for &side in Side::ALL.iter() {
+            if side == Side::Black {
+                signature.push('v');
+            }
+            for &ptype in PieceType::ALL.iter().rev() {
+                let piece = Piece { ptype, side };
+                for _ in 0..self.count(piece) {
+                    signature.push(piece.to_fen_char().to_ascii_uppercase());
+                }
+            }
+        }
+
+        write!(f, "{}", signature)
+    }
 }
 
 /// Little-endian rank-file index of a square
 ///
-/// ```
+/// ```text
 /// lerf_index = rank_index * 8 + file_index
 /// ```
 fn lerf_index_for(square: &str) -> Result<u8, InvalidSquareError> {
-    let filtre = Regex::new(r"(?i)[a-h][1-8]").unwrap();
-    if !filtre.is_match(square) {
-        return Err(InvalidSquareError {
-            msg: square.to_string(),
-        });
-    }
-
-    let sqre = Regex::new(r"(?P<file>[a-h])(?P<rank>[1-8])").unwrap();
-    let captures = sqre.captures(square).unwrap();
+    static SQUARE: OnceLock<Regex> = OnceLock::new();
+    let sqre = SQUARE.get_or_init(|| Regex::new(r"^(?i)(?P<file>[a-h])(?P<rank>[1-8])$").unwrap());
+    let captures = sqre.captures(square).ok_or_else(|| InvalidSquareError {
+        msg: square.to_string(),
+    })?;
     let rank_str = &captures["rank"];
     let file_str = &captures["file"];
 
-    let file_chr = file_str.chars().next().unwrap();
+    let file_chr = file_str.chars().next().unwrap().to_ascii_lowercase();
 
     let file_index = file_index_of(file_chr).unwrap();
     let rank_index = u8::from_str(rank_str).unwrap() - 1;
@@ -300,30 +967,19 @@ fn lerf_index_for(square: &str) -> Result<u8, InvalidSquareError> {
 
 /// Maps a file to its numerical index
 ///
-/// ```
+/// ```text
 /// a = 0,
 /// b = 1,
 /// ...
 /// h = 7
 /// ```
 pub fn file_index_of(file: char) -> Result<u8, InvalidFileError> {
-    let chrindex = file.to_digit(18);
-    if chrindex.is_none() {
-        return Err(InvalidFileError {
-            msg: format!("File out of range: {}", file),
-        });
+    match file {
+        'a'..='h' => Ok(file as u8 - b'a'),
+        _ => Err(InvalidFileError {
+            msg: file.to_string(),
+        }),
     }
-
-    let index = chrindex.unwrap() - 'a'.to_digit(18).unwrap();
-
-    let index = index as u8;
-    if index < 8 {
-        return Ok(index);
-    }
-
-    Err(InvalidFileError {
-        msg: file.to_string(),
-    })
 }
 
 /// Maps a numerical index to a file
@@ -341,7 +997,13 @@ pub fn file_for_index(index: u8) -> Result<String, InvalidFileError> {
 
 #[cfg(test)]
 mod tests {
+    use super::consts::{DARK_SQUARES, LIGHT_SQUARES};
     use super::*;
+    use crate::fixtures::square;
+
+    fn bit_board(square: &str) -> BitBoard {
+        BitBoard::try_from(square).unwrap()
+    }
 
     #[test]
     fn test_file_index_of() {
@@ -349,20 +1011,27 @@ mod tests {
         assert_eq!(Ok(4), file_index_of('e'));
         assert_eq!(Ok(7), file_index_of('h'));
         assert!(file_index_of('j').is_err());
+        assert!(file_index_of('1').is_err());
+        assert!(file_index_of('A').is_err());
     }
 
     #[test]
     fn test_bit_for_square() {
-        assert_eq!(BitBoard(0x0100000000000000), BitBoard::from("a8"));
-        assert_eq!(BitBoard(0x0000000000000001), BitBoard::from("a1"));
-        assert_eq!(BitBoard(0x8000000000000000), BitBoard::from("h8"));
-        assert_eq!(BitBoard(0x0004000000000000), BitBoard::from("c7"));
+        assert_eq!(BitBoard(0x0100000000000000), bit_board("a8"));
+        assert_eq!(BitBoard(0x0000000000000001), bit_board("a1"));
+        assert_eq!(BitBoard(0x8000000000000000), bit_board("h8"));
+        assert_eq!(BitBoard(0x0004000000000000), bit_board("c7"));
     }
 
     #[test]
-    #[should_panic]
     fn test_bit_for_square_with_bad_input() {
-        BitBoard::from("bad input");
+        assert!(BitBoard::try_from("bad input").is_err());
+        assert!(BitBoard::try_from("a1b").is_err());
+        assert!(BitBoard::try_from("i4").is_err());
+        assert!(BitBoard::try_from("11").is_err());
+        assert!(Square::try_from("11").is_err());
+        assert_eq!(bit_board("e4"), "E4".parse().unwrap());
+        assert!(Board::new().get_square("e9").is_err());
     }
 
     #[test]
@@ -374,6 +1043,138 @@ mod tests {
         assert_eq!(0xf1f1f1 ^ 0xf1f1f1, BitBoard(0).0);
     }
 
+    #[test]
+    fn test_bit_board_shift() {
+        let d4 = bit_board("d4");
+
+        assert_eq!(bit_board("d5"), d4.shift(Direction::North));
+        assert_eq!(bit_board("e5"), d4.shift(Direction::NorthEast));
+        assert_eq!(bit_board("c3"), d4.shift(Direction::SouthWest));
+        assert_eq!(BitBoard(0), bit_board("h4").shift(Direction::East));
+        assert_eq!(BitBoard(0), bit_board("h4").shift(Direction::NorthEast));
+        assert_eq!(BitBoard(0), bit_board("a4").shift(Direction::West));
+        assert_eq!(BitBoard(0), bit_board("a4").shift(Direction::SouthWest));
+        assert_eq!(BitBoard(0), bit_board("e8").shift(Direction::North));
+        assert_eq!(BitBoard(0), bit_board("e1").shift(Direction::South));
+    }
+
+    #[test]
+    fn test_pawn_pushes() {
+        let white_pawns = BitBoard(WHITE_PAWN_START_POS);
+        let empty = !(white_pawns | bit_board("c3") | bit_board("e4"));
+
+        assert_eq!(
+            BitBoard(0x0000000000fb0000),
+            pawn_push_targets(white_pawns, empty, Side::White)
+        );
+        assert_eq!(
+            BitBoard(0x00000000eb000000),
+            pawn_double_push_targets(white_pawns, empty, Side::White)
+        );
+        assert_eq!(
+            bit_board("a6"),
+            pawn_push_targets(bit_board("a7"), !BitBoard(0), Side::Black)
+        );
+        assert_eq!(
+            BitBoard(0),
+            pawn_double_push_targets(bit_board("a6"), !BitBoard(0), Side::Black)
+        );
+    }
+
+    #[test]
+    fn test_pawn_attacks() {
+        assert_eq!(bit_board("b3"), pawn_attacks(bit_board("a2"), Side::White));
+        assert_eq!(
+            bit_board("d6") ^ bit_board("f6"),
+            pawn_attacks(bit_board("e7"), Side::Black)
+        );
+        assert_eq!(bit_board("g7"), pawn_attacks(bit_board("h8"), Side::Black));
+    }
+
+    #[test]
+    fn test_square() {
+        let square = Square::try_from("c7").unwrap();
+
+        assert_eq!(50, square.index());
+        assert_eq!(2, square.file_index());
+        assert_eq!(6, square.rank_index());
+        assert_eq!("c7", square.to_string());
+        assert_eq!(bit_board("c7"), BitBoard::from(square));
+        assert_eq!(square, Square::from_index(50).unwrap());
+        assert!(Square::from_index(64).is_err());
+    }
+
+    #[test]
+    fn test_square_color() {
+        assert_eq!(SquareColor::Dark, square("a1").color());
+        assert_eq!(SquareColor::Light, square("h1").color());
+        assert_eq!(SquareColor::Dark, square("h8").color());
+        assert!(same_color_squares(square("c1"), square("h6")));
+        assert!(!same_color_squares(square("c1"), square("f1")));
+        assert_eq!(BitBoard(!0), LIGHT_SQUARES | DARK_SQUARES);
+        for index in 0..64 {
+            let square = Square::from_index(index).unwrap();
+            let expected = match square.color() {
+                SquareColor::Light => LIGHT_SQUARES,
+                SquareColor::Dark => DARK_SQUARES,
+            };
+            assert_eq!(BitBoard::from(square), BitBoard::from(square) & expected);
+        }
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(0, distance(square("e4"), square("e4")));
+        assert_eq!(7, distance(square("a1"), square("h8")));
+        assert_eq!(3, distance(square("b2"), square("e4")));
+        assert_eq!(14, manhattan_distance(square("a8"), square("h1")));
+        assert_eq!(5, manhattan_distance(square("b2"), square("e4")));
+    }
+
+    #[test]
+    fn test_bit_board_squares() {
+        let squares: Vec<String> = (bit_board("h8") ^ bit_board("b1") ^ bit_board("e4"))
+            .squares()
+            .map(|square| square.to_string())
+            .collect();
+
+        assert_eq!(vec!["b1", "e4", "h8"], squares);
+        assert_eq!(0, BitBoard(0).squares().count());
+        assert_eq!(
+            16,
+            BitBoard(WHITE_PAWN_START_POS | BLACK_PAWN_START_POS).count()
+        );
+    }
+
+    #[test]
+    fn test_board_occupancy() {
+        let board = Board::new();
+
+        assert_eq!(BitBoard(0x000000000000ffff), board.occupancy(Side::White));
+        assert_eq!(BitBoard(0xffff000000000000), board.occupancy(Side::Black));
+        assert_eq!(BitBoard(0xffff00000000ffff), board.occupied());
+        assert_eq!(
+            board.get_square("d8").unwrap(),
+            board.piece_on(Square::try_from("d8").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_attack_map() {
+        let board = Board::new();
+        let white = board.attack_map(Side::White);
+        let index = |square| Square::try_from(square).unwrap().index() as usize;
+
+        assert_eq!(3, white[index("f3")]);
+        assert_eq!(2, white[index("d3")]);
+        assert_eq!(1, white[index("d1")]);
+        assert_eq!(0, white[index("e4")]);
+        assert_eq!(0, white[index("a1")]);
+
+        let black = board.color_flipped().attack_map(Side::Black);
+        assert!((0..64).all(|index| white[index] == black[index ^ 56]));
+    }
+
     #[test]
     fn test_pieceset_initialization() {
         let board = Board::new();
@@ -401,35 +1202,53 @@ mod tests {
     fn test_8x8_board_initialization() {
         let board = _8x8Board::new();
 
-        assert_eq!(None, board.get_square(&"a4"));
-        assert_eq!(None, board.get_square(&"d5"));
+        assert_eq!(None, board.get_square("a4").unwrap());
+        assert_eq!(None, board.get_square("d5").unwrap());
         assert_eq!(
             Some(Piece {
                 side: Side::White,
                 ptype: PieceType::Queen
             }),
-            board.get_square(&"d1")
+            board.get_square("d1").unwrap()
         );
         assert_eq!(
             Some(Piece {
                 side: Side::White,
                 ptype: PieceType::King
             }),
-            board.get_square(&"e1")
+            board.get_square("e1").unwrap()
         );
         assert_eq!(
             Some(Piece {
                 side: Side::Black,
                 ptype: PieceType::Queen
             }),
-            board.get_square(&"d8")
+            board.get_square("d8").unwrap()
         );
         assert_eq!(
             Some(Piece {
                 side: Side::Black,
                 ptype: PieceType::Bishop
             }),
-            board.get_square(&"f8")
+            board.get_square("f8").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_8x8_board_try_from_letters() {
+        assert_eq!(
+            _8x8Board::new(),
+            _8x8Board::try_from([
+                ["r", "n", "b", "q", "k", "b", "n", "r"],
+                ["p", "p", "p", "p", "p", "p", "p", "p"],
+                ["", "", "", "", "", "", "", ""],
+                ["", "", "", "", "", "", "", ""],
+                ["", "", "", "", "", "", "", ""],
+                ["", "", "", "", "", "", "", ""],
+                ["♙", "♙", "♙", "♙", "♙", "♙", "♙", "♙"],
+                ["R", "N", "B", "Q", "K", "B", "N", "R"],
+            ])
+            .unwrap()
         );
     }
 
@@ -441,9 +1260,190 @@ mod tests {
             ptype: PieceType::Pawn,
         };
 
-        assert!(!board.set_square("a1", Some(piece)).is_err());
+        assert!(board.set_square("a1", Some(piece)).is_ok());
+
+        assert_eq!(Some(piece), board.get(0));
+    }
+
+    #[test]
+    fn test_8x8_board_from_ascii_diagram() {
+        let board = _8x8Board::new();
+
+        assert_eq!(
+            board,
+            _8x8Board::from_ascii_diagram(&board.to_string()).unwrap()
+        );
+        assert_eq!(
+            board,
+            _8x8Board::from_ascii_diagram(
+                "rnbqkbnr
+                 pppppppp
+                 ........
+                 ........
+                 ........
+                 ........
+                 PPPPPPPP
+                 RNBQKBNR"
+            )
+            .unwrap()
+        );
+    }
 
-        assert_eq!(Some(piece), board.0[0][0]);
+    #[test]
+    fn test_8x8_board_render() {
+        let board = _8x8Board::new();
+        let letters = board.render(&Theme::letters());
+        let inverted = board.render(&Theme::inverted());
+
+        assert_eq!(board.to_string(), board.render(&Theme::unicode()));
+        assert!(letters.starts_with("8 rnbqkbnr\n7 pppppppp\n6 ........\n"));
+        assert_eq!(board, _8x8Board::from_ascii_diagram(&letters).unwrap());
+        assert!(inverted.starts_with("8 ♖♘♗♕♔♗♘♖\n"));
+        assert_eq!(
+            "♟",
+            Theme::inverted().symbol(Piece {
+                ptype: PieceType::Pawn,
+                side: Side::White,
+            })
+        );
+    }
+
+    #[test]
+    fn test_themed_diagram_round_trip() {
+        let board = Board::from_ascii_diagram(
+            "....k...
+             .p......
+             ........
+             ..N..b..
+             ........
+             ........
+             ......Q.
+             R...K...",
+        )
+        .unwrap();
+        let emoji = Theme::new(
+            ["🐑", "🐴", "⛪", "🏰", "👸", "🤴"],
+            ["🐐", "🦄", "🕌", "🗼", "🧙‍♀️", "🧙"],
+            "⬜",
+        );
+
+        for theme in [Theme::unicode(), Theme::inverted(), Theme::letters(), emoji].iter() {
+            let read_back = Board::from_themed_diagram(&board.render(theme), theme).unwrap();
+
+            assert_eq!(board.render(theme), read_back.render(theme));
+            assert_eq!(
+                Some(Piece {
+                    ptype: PieceType::King,
+                    side: Side::White,
+                }),
+                read_back.get_square("e1").unwrap()
+            );
+        }
+        assert_eq!(
+            board.render(&Theme::letters()),
+            Board::from_ascii_diagram(&board.render(&Theme::letters()))
+                .unwrap()
+                .render(&Theme::letters())
+        );
+    }
+
+    #[test]
+    fn test_8x8_board_from_bad_ascii_diagram() {
+        assert!(_8x8Board::from_ascii_diagram("").is_err());
+        assert!(_8x8Board::from_ascii_diagram(&"........\n".repeat(7)).is_err());
+        assert!(_8x8Board::from_ascii_diagram(&"........\n".repeat(9)).is_err());
+        assert!(_8x8Board::from_ascii_diagram(&"..x.....\n".repeat(8)).is_err());
+        assert!(_8x8Board::from_ascii_diagram(&"1 ........\n".repeat(8)).is_err());
+    }
+
+    #[test]
+    fn test_board_from_ascii_diagram() {
+        let board = Board::from_ascii_diagram(
+            "8 . . . . k . . .
+             7 . . . . . . . .
+             6 . . . . . . . .
+             5 . . . . . . . .
+             4 . . . . . . . .
+             3 . . . . . . . .
+             2 . . . . . . . .
+             1 R . . . K . . R
+               a b c d e f g h",
+        )
+        .unwrap();
+        let white_rook = Piece {
+            side: Side::White,
+            ptype: PieceType::Rook,
+        };
+
+        assert_eq!(BitBoard(0x81), board.bit_board_for(white_rook));
+        assert_eq!(Some(white_rook), board.get_square("h1").unwrap());
+        assert_eq!(
+            bit_board("e8"),
+            board.bit_board_for(Piece {
+                side: Side::Black,
+                ptype: PieceType::King
+            })
+        );
+    }
+
+    #[test]
+    fn test_board_from_8x8_board_matches_new() {
+        let board = Board::from(_8x8Board::new());
+        let new_board = Board::new();
+
+        for &side in Side::ALL.iter() {
+            for &ptype in PieceType::ALL.iter() {
+                let piece = Piece { side, ptype };
+                assert_eq!(new_board.bit_board_for(piece), board.bit_board_for(piece));
+            }
+        }
+    }
+
+    #[test]
+    fn test_material_key() {
+        let board = Board::from_ascii_diagram(
+            "....k...
+             ....r...
+             ........
+             ........
+             ........
+             ........
+             ...P....
+             ....K..R",
+        )
+        .unwrap();
+        let black_rook = Piece {
+            side: Side::Black,
+            ptype: PieceType::Rook,
+        };
+
+        assert_eq!("KRPvKR", board.material().to_string());
+        assert_eq!(1, board.material().count(black_rook));
+        assert_eq!(
+            8,
+            Board::new().material().count(Piece {
+                side: Side::White,
+                ptype: PieceType::Pawn
+            })
+        );
+        assert_ne!(Board::new().material(), board.material());
+    }
+
+    #[test]
+    fn test_material_key_follows_bit_boards() {
+        let mut board = Board::new();
+        let black_knight = Piece {
+            side: Side::Black,
+            ptype: PieceType::Knight,
+        };
+
+        board.set_bit_board(bit_board("g8"), black_knight);
+
+        assert_eq!(1, board.material().count(black_knight));
+        assert_eq!(
+            Board::from(_8x8Board::new()).material(),
+            Board::new().material()
+        );
     }
 
     #[test]
@@ -461,4 +1461,51 @@ mod tests {
         assert_eq!(BitBoard(0xe), board.black.queens);
         assert_eq!(BitBoard(WHITE_QUEEN_START_POS), board.white.queens);
     }
+
+    #[test]
+    fn test_board_color_flipped() {
+        let board = Board::from_ascii_diagram(
+            "....k...
+             ........
+             ........
+             ...p....
+             ........
+             ........
+             .....N..
+             ....K...",
+        )
+        .unwrap();
+        let flipped = Board::from_ascii_diagram(
+            "....k...
+             .....n..
+             ........
+             ........
+             ...P....
+             ........
+             ........
+             ....K...",
+        )
+        .unwrap();
+
+        assert_eq!(flipped.squares, board.color_flipped().squares);
+        assert_eq!(flipped.material(), board.color_flipped().material());
+        assert_eq!(Board::new().squares, Board::new().color_flipped().squares);
+    }
+
+    #[test]
+    fn test_board_copy() {
+        let board = Board::new();
+        let mut copy = board;
+
+        copy.set_square("e2", None).unwrap();
+
+        // One byte per square at most
+        assert!(std::mem::size_of::<_8x8Board>() <= 64);
+        assert_eq!(None, copy.get_square("e2").unwrap());
+        assert_eq!(
+            board.get_square("e1").unwrap(),
+            copy.get_square("e1").unwrap()
+        );
+        assert!(board.get_square("e2").unwrap().is_some());
+    }
 }
@@ -0,0 +1,72 @@
+//! Named bitboards for common groups of squares
+
+use super::BitBoard;
+
+pub const FILE_A: BitBoard = BitBoard(0x0101010101010101);
+pub const FILE_B: BitBoard = BitBoard(0x0202020202020202);
+pub const FILE_C: BitBoard = BitBoard(0x0404040404040404);
+pub const FILE_D: BitBoard = BitBoard(0x0808080808080808);
+pub const FILE_E: BitBoard = BitBoard(0x1010101010101010);
+pub const FILE_F: BitBoard = BitBoard(0x2020202020202020);
+pub const FILE_G: BitBoard = BitBoard(0x4040404040404040);
+pub const FILE_H: BitBoard = BitBoard(0x8080808080808080);
+
+/// Files from a to h, indexed by file index
+pub const FILES: [BitBoard; 8] = [
+    FILE_A, FILE_B, FILE_C, FILE_D, FILE_E, FILE_F, FILE_G, FILE_H,
+];
+
+pub const RANK_1: BitBoard = BitBoard(0x00000000000000ff);
+pub const RANK_2: BitBoard = BitBoard(0x000000000000ff00);
+pub const RANK_3: BitBoard = BitBoard(0x0000000000ff0000);
+pub const RANK_4: BitBoard = BitBoard(0x00000000ff000000);
+pub const RANK_5: BitBoard = BitBoard(0x000000ff00000000);
+pub const RANK_6: BitBoard = BitBoard(0x0000ff0000000000);
+pub const RANK_7: BitBoard = BitBoard(0x00ff000000000000);
+pub const RANK_8: BitBoard = BitBoard(0xff00000000000000);
+
+/// Ranks from 1 to 8, indexed by rank index
+pub const RANKS: [BitBoard; 8] = [
+    RANK_1, RANK_2, RANK_3, RANK_4, RANK_5, RANK_6, RANK_7, RANK_8,
+];
+
+/// The a1-h8 diagonal
+pub const LONG_DIAGONAL: BitBoard = BitBoard(0x8040201008040201);
+/// The h1-a8 diagonal
+pub const LONG_ANTI_DIAGONAL: BitBoard = BitBoard(0x0102040810204080);
+
+/// d4, e4, d5 and e5
+pub const CENTER: BitBoard = BitBoard(0x0000001818000000);
+/// The sixteen squares from c3 to f6
+pub const EXTENDED_CENTER: BitBoard = BitBoard(0x00003c3c3c3c0000);
+
+/// Squares on the outer files and ranks
+pub const EDGES: BitBoard = BitBoard(FILE_A.0 | FILE_H.0 | RANK_1.0 | RANK_8.0);
+/// a1, h1, a8 and h8
+pub const CORNERS: BitBoard = BitBoard(0x8100000000000081);
+
+/// Squares of the same colour as h1
+pub const LIGHT_SQUARES: BitBoard = BitBoard(0x55aa55aa55aa55aa);
+/// Squares of the same colour as a1
+pub const DARK_SQUARES: BitBoard = BitBoard(0xaa55aa55aa55aa55);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_masks() {
+        for index in 0..8 {
+            assert_eq!(8, FILES[index].count());
+            assert_eq!(8, RANKS[index].count());
+            assert_eq!(1, (FILES[index] & RANKS[index] & LONG_DIAGONAL).count());
+        }
+
+        assert_eq!(BitBoard::try_from("e4").unwrap(), CENTER & FILE_E & RANK_4);
+        assert_eq!(CENTER, EXTENDED_CENTER & CENTER);
+        assert_eq!(12, EXTENDED_CENTER.count() - CENTER.count());
+        assert_eq!(28, EDGES.count());
+        assert_eq!(CORNERS, EDGES & (LONG_DIAGONAL | LONG_ANTI_DIAGONAL));
+    }
+}
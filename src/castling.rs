@@ -0,0 +1,223 @@
+use std::fmt;
+
+use crate::board::Square;
+use crate::types::*;
+
+/// Side of the board a king castles towards
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Wing {
+    Kingside,
+    Queenside,
+}
+
+impl Wing {
+    pub const ALL: [Wing; 2] = [Wing::Kingside, Wing::Queenside];
+}
+
+/// Which castling moves each side may still make, and where the castling
+/// kings and rooks started
+///
+/// Rights are kept as one bit per side and wing. The king and rook files
+/// are stored so that Chess960 starting positions are handled the same way
+/// as the standard one.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CastlingRights {
+    flags: u8,
+    king_file: u8,
+    /// Kingside then queenside rook file
+    rook_files: [u8; 2],
+}
+
+impl CastlingRights {
+    /// Every right, with the king on the e-file and rooks on the a- and
+    /// h-files
+    pub fn new() -> CastlingRights {
+        CastlingRights::chess960(4, 7, 0).unwrap()
+    }
+
+    /// Every right, with the king and rooks on the given files
+    ///
+    /// The king must stand between the queenside and kingside rooks, and
+    /// all three files must be on the board.
+    pub fn chess960(
+        king_file: u8,
+        kingside_rook_file: u8,
+        queenside_rook_file: u8,
+    ) -> Result<CastlingRights, InvalidCastlingError> {
+        if kingside_rook_file > 7
+            || !(queenside_rook_file < king_file && king_file < kingside_rook_file)
+        {
+            return Err(InvalidCastlingError {
+                msg: format!(
+                    "king on file {}, rooks on files {} and {}",
+                    king_file, kingside_rook_file, queenside_rook_file
+                ),
+            });
+        }
+
+        Ok(CastlingRights {
+            flags: 0b1111,
+            king_file,
+            rook_files: [kingside_rook_file, queenside_rook_file],
+        })
+    }
+
+    /// No rights for either side
+    pub fn none() -> CastlingRights {
+        CastlingRights {
+            flags: 0,
+            ..CastlingRights::new()
+        }
+    }
+
+    pub fn has(&self, side: Side, wing: Wing) -> bool {
+        self.flags & flag(side, wing) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.flags == 0
+    }
+
+    pub fn add(&mut self, side: Side, wing: Wing) {
+        self.flags |= flag(side, wing);
+    }
+
+    pub fn remove(&mut self, side: Side, wing: Wing) {
+        self.flags &= !flag(side, wing);
+    }
+
+    /// File the rook castling on a wing starts on
+    pub fn rook_file(&self, wing: Wing) -> u8 {
+        self.rook_files[wing as usize]
+    }
+
+    /// Square a side's rook castling on a wing starts on
+    pub fn rook_square(&self, side: Side, wing: Wing) -> Square {
+        Square::from_index(side.relative_rank(0) * 8 + self.rook_file(wing)).unwrap()
+    }
+
+    /// Square a side's king starts on
+    pub fn king_square(&self, side: Side) -> Square {
+        Square::from_index(side.relative_rank(0) * 8 + self.king_file).unwrap()
+    }
+
+    /// Drops the rights lost by a move between two squares
+    ///
+    /// Moving a king loses both of its side's rights. Moving a rook from its
+    /// starting square, or capturing a rook on it, loses that rook's right.
+    pub fn remove_on_move(&mut self, from: Square, to: Square) {
+        for &side in Side::ALL.iter() {
+            if from == self.king_square(side) {
+                self.remove(side, Wing::Kingside);
+                self.remove(side, Wing::Queenside);
+            }
+            for &wing in Wing::ALL.iter() {
+                let rook = self.rook_square(side, wing);
+                if from == rook || to == rook {
+                    self.remove(side, wing);
+                }
+            }
+        }
+    }
+}
+
+impl Default for CastlingRights {
+    fn default() -> Self {
+        CastlingRights::new()
+    }
+}
+
+impl fmt::Display for CastlingRights {
+    /// Writes the rights as a Shredder-FEN castling field
+    ///
+    /// Each right is written as its rook's file letter, uppercase for
+    /// white, so the standard starting rights are `HAha`. Unlike `KQkq`,
+    /// this needs no other pieces to tell which rook castles.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "-");
+        }
+
+        for &side in Side::ALL.iter() {
+            for &wing in Wing::ALL.iter() {
+                if !self.has(side, wing) {
+                    continue;
+                }
+
+                let letter = (b'a' + self.rook_file(wing)) as char;
+                let letter = match side {
+                    Side::White => letter.to_ascii_uppercase(),
+                    Side::Black => letter,
+                };
+                write!(f, "{}", letter)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn flag(side: Side, wing: Wing) -> u8 {
+    1 << (side.index() * 2 + wing as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::square;
+
+    #[test]
+    fn test_display() {
+        let mut rights = CastlingRights::new();
+
+        assert_eq!("HAha", rights.to_string());
+        rights.remove(Side::White, Wing::Kingside);
+        rights.remove(Side::Black, Wing::Queenside);
+        assert_eq!("Ah", rights.to_string());
+        assert_eq!("-", CastlingRights::none().to_string());
+        assert_eq!(
+            "FBfb",
+            CastlingRights::chess960(3, 5, 1).unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_remove_on_move() {
+        let mut rights = CastlingRights::new();
+
+        rights.remove_on_move(square("h1"), square("h5"));
+        assert_eq!("Aha", rights.to_string());
+        rights.remove_on_move(square("b2"), square("a8"));
+        assert_eq!("Ah", rights.to_string());
+        rights.remove_on_move(square("e8"), square("d7"));
+        assert_eq!("A", rights.to_string());
+        rights.remove_on_move(square("e1"), square("c1"));
+        assert!(rights.is_empty());
+        assert_eq!("-", rights.to_string());
+    }
+
+    #[test]
+    fn test_chess960() {
+        let mut rights = CastlingRights::chess960(1, 6, 0).unwrap();
+
+        assert_eq!(
+            square("g8"),
+            rights.rook_square(Side::Black, Wing::Kingside)
+        );
+        assert_eq!("GAga", rights.to_string());
+        rights.remove_on_move(square("e1"), square("e2"));
+        assert_eq!("GAga", rights.to_string());
+        rights.remove_on_move(square("b1"), square("c1"));
+        assert!(!rights.has(Side::White, Wing::Queenside));
+        assert!(rights.has(Side::Black, Wing::Queenside));
+        assert_eq!("ga", rights.to_string());
+    }
+
+    #[test]
+    fn test_chess960_rejects_bad_files() {
+        assert!(CastlingRights::chess960(4, 8, 0).is_err());
+        assert!(CastlingRights::chess960(4, 2, 0).is_err());
+        assert!(CastlingRights::chess960(4, 7, 5).is_err());
+        assert!(CastlingRights::chess960(0, 7, 0).is_err());
+    }
+}
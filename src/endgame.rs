@@ -0,0 +1,473 @@
+use crate::board::consts::{CORNERS, DARK_SQUARES, LIGHT_SQUARES};
+use crate::board::{
+    distance, manhattan_distance, BitBoard, Board, Direction, MaterialKey, Square, SquareColor,
+};
+use crate::types::*;
+
+/// Score for a position that is won with correct play, in centipawns
+pub const KNOWN_WIN: i32 = 10000;
+
+const PAWN_VALUE: i32 = 100;
+const KNIGHT_VALUE: i32 = 320;
+const BISHOP_VALUE: i32 = 330;
+const ROOK_VALUE: i32 = 500;
+const QUEEN_VALUE: i32 = 900;
+
+/// Corners a dark-squared bishop controls
+const DARK_CORNERS: BitBoard = BitBoard(CORNERS.0 & DARK_SQUARES.0);
+/// Corners a light-squared bishop controls
+const LIGHT_CORNERS: BitBoard = BitBoard(CORNERS.0 & LIGHT_SQUARES.0);
+
+/// Material configurations with dedicated evaluation
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Endgame {
+    /// Mating material against a bare king
+    KXK,
+    /// King, bishop and knight against a bare king
+    KBNK,
+    /// King and queen against king and pawn
+    KQKP,
+    /// King and rook against king and pawn
+    KRKP,
+}
+
+/// Finds the specialized endgame for a material signature, along with the
+/// side that has the extra material
+pub fn classify(material: MaterialKey) -> Option<(Endgame, Side)> {
+    for &strong_side in Side::ALL.iter() {
+        let strong = counts(material, strong_side);
+        let weak = counts(material, strong_side.opposite());
+
+        let endgame = if weak == [0; 5] {
+            if strong == [0, 1, 1, 0, 0] {
+                Some(Endgame::KBNK)
+            } else if has_mating_material(strong) {
+                Some(Endgame::KXK)
+            } else {
+                None
+            }
+        } else if strong == [0, 0, 0, 0, 1] && weak == [1, 0, 0, 0, 0] {
+            Some(Endgame::KQKP)
+        } else if strong == [0, 0, 0, 1, 0] && weak == [1, 0, 0, 0, 0] {
+            Some(Endgame::KRKP)
+        } else {
+            None
+        };
+
+        if let Some(endgame) = endgame {
+            return Some((endgame, strong_side));
+        }
+    }
+
+    None
+}
+
+/// Evaluates the board with a specialized endgame routine, if its material
+/// has one
+///
+/// Scores are in centipawns from white's point of view, and should be used
+/// in place of the general evaluation.
+///
+/// With the `symmetry-check` feature, debug builds also evaluate the
+/// colour-flipped board and panic unless the score is exactly negated.
+pub fn evaluate(board: &Board) -> Option<i32> {
+    let score = evaluate_unchecked(board);
+
+    #[cfg(feature = "symmetry-check")]
+    debug_assert_eq!(
+        score,
+        evaluate_unchecked(&board.color_flipped()).map(|flipped| -flipped),
+        "asymmetric endgame evaluation of\n{}",
+        board.render(&crate::board::Theme::letters())
+    );
+
+    score
+}
+
+fn evaluate_unchecked(board: &Board) -> Option<i32> {
+    let (endgame, strong_side) = classify(board.material())?;
+    for &side in Side::ALL.iter() {
+        let king = Piece {
+            ptype: PieceType::King,
+            side,
+        };
+        if board.material().count(king) != 1 {
+            return None;
+        }
+    }
+
+    let score = match endgame {
+        Endgame::KXK => evaluate_kxk(board, strong_side),
+        Endgame::KBNK => evaluate_kbnk(board, strong_side),
+        Endgame::KQKP => evaluate_kqkp(board, strong_side),
+        Endgame::KRKP => evaluate_krkp(board, strong_side),
+    };
+
+    match strong_side {
+        Side::White => Some(score),
+        Side::Black => Some(-score),
+    }
+}
+
+/// Drives the bare king to the edge of the board and brings the
+/// attacking king closer to it
+fn evaluate_kxk(board: &Board, strong_side: Side) -> i32 {
+    let strong_king = piece_square(board, PieceType::King, strong_side);
+    let weak_king = piece_square(board, PieceType::King, strong_side.opposite());
+    let strong = counts(board.material(), strong_side);
+    let material: i32 = [
+        PAWN_VALUE,
+        KNIGHT_VALUE,
+        BISHOP_VALUE,
+        ROOK_VALUE,
+        QUEEN_VALUE,
+    ]
+    .iter()
+    .zip(strong.iter())
+    .map(|(&value, &count)| value * count as i32)
+    .sum();
+
+    KNOWN_WIN + material + push_to_edge(weak_king) + push_close(strong_king, weak_king)
+}
+
+/// Drives the bare king towards a corner the bishop controls, since mate
+/// can only be forced there
+fn evaluate_kbnk(board: &Board, strong_side: Side) -> i32 {
+    let strong_king = piece_square(board, PieceType::King, strong_side);
+    let weak_king = piece_square(board, PieceType::King, strong_side.opposite());
+    let bishop = piece_square(board, PieceType::Bishop, strong_side);
+    let corners = if bishop.color() == SquareColor::Dark {
+        DARK_CORNERS
+    } else {
+        LIGHT_CORNERS
+    };
+    let corner_distance = corners
+        .squares()
+        .map(|corner| manhattan_distance(weak_king, corner) as i32)
+        .min()
+        .unwrap();
+
+    KNOWN_WIN
+        + KNIGHT_VALUE
+        + BISHOP_VALUE
+        + 20 * (14 - corner_distance)
+        + push_close(strong_king, weak_king)
+}
+
+/// Queen against pawn is won unless a rook or bishop pawn on the seventh
+/// rank is supported by its king, which draws by stalemate tricks
+fn evaluate_kqkp(board: &Board, strong_side: Side) -> i32 {
+    let weak_side = strong_side.opposite();
+    let strong_king = piece_square(board, PieceType::King, strong_side);
+    let weak_king = piece_square(board, PieceType::King, weak_side);
+    let pawn = piece_square(board, PieceType::Pawn, weak_side);
+    let drawing_file = [0, 2, 5, 7].contains(&pawn.file_index());
+
+    let mut score = push_close(strong_king, weak_king);
+    if weak_side.relative_rank(pawn.rank_index()) != 6
+        || distance(weak_king, pawn) != 1
+        || !drawing_file
+    {
+        score += QUEEN_VALUE - PAWN_VALUE;
+    }
+
+    score
+}
+
+/// Rook against pawn is won when the attacking king stops the pawn or the
+/// defending king is too far away, and drawish when the pawn is far
+/// advanced with its king in support
+fn evaluate_krkp(board: &Board, strong_side: Side) -> i32 {
+    let weak_side = strong_side.opposite();
+    let strong_king = piece_square(board, PieceType::King, strong_side);
+    let weak_king = piece_square(board, PieceType::King, weak_side);
+    let rook = piece_square(board, PieceType::Rook, strong_side);
+    let pawn = piece_square(board, PieceType::Pawn, weak_side);
+    let queening_square = Square::from_index(match weak_side {
+        Side::White => 56 + pawn.file_index(),
+        Side::Black => pawn.file_index(),
+    })
+    .unwrap();
+    let distance = |a, b| distance(a, b) as i32;
+    let king_in_front = strong_king.file_index() == pawn.file_index()
+        && weak_side.relative_rank(strong_king.rank_index())
+            > weak_side.relative_rank(pawn.rank_index());
+
+    if king_in_front || (distance(weak_king, pawn) >= 3 && distance(weak_king, rook) >= 3) {
+        ROOK_VALUE - distance(strong_king, pawn)
+    } else if strong_side.relative_rank(weak_king.rank_index()) <= 2
+        && distance(weak_king, pawn) == 1
+        && strong_side.relative_rank(strong_king.rank_index()) >= 3
+        && distance(strong_king, pawn) > 2
+    {
+        80 - 8 * distance(strong_king, pawn)
+    } else {
+        let stop_square = BitBoard::from(pawn)
+            .shift(Direction::forward(weak_side))
+            .squares()
+            .next()
+            .unwrap_or(queening_square);
+        200 - 8
+            * (distance(strong_king, stop_square)
+                - distance(weak_king, stop_square)
+                - distance(pawn, queening_square))
+    }
+}
+
+/// Piece counts of one side from pawn to queen
+fn counts(material: MaterialKey, side: Side) -> [u32; 5] {
+    let mut counts = [0; 5];
+    for (count, &ptype) in counts.iter_mut().zip(PieceType::ALL.iter()) {
+        *count = material.count(Piece { ptype, side });
+    }
+
+    counts
+}
+
+fn has_mating_material(counts: [u32; 5]) -> bool {
+    let [_, knights, bishops, rooks, queens] = counts;
+
+    queens > 0 || rooks > 0 || bishops > 1 || (bishops > 0 && knights > 0)
+}
+
+/// Square of a side's first piece of a type
+fn piece_square(board: &Board, ptype: PieceType, side: Side) -> Square {
+    board
+        .bit_board_for(Piece { ptype, side })
+        .squares()
+        .next()
+        .unwrap()
+}
+
+/// Bonus for a king standing further from the centre
+fn push_to_edge(square: Square) -> i32 {
+    let file = square.file_index() as i32;
+    let rank = square.rank_index() as i32;
+    let centre_distance = (3 - file).max(file - 4) + (3 - rank).max(rank - 4);
+
+    20 * centre_distance
+}
+
+/// Bonus for two kings standing close together
+fn push_close(a: Square, b: Square) -> i32 {
+    140 - 20 * distance(a, b) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::board;
+
+    #[test]
+    fn test_classify() {
+        let krk = board(
+            "....k...
+             ........
+             ........
+             ........
+             ........
+             ........
+             ........
+             ....K..R",
+        );
+        let kbnk = board(
+            "....K...
+             ........
+             ........
+             ........
+             ........
+             ........
+             ........
+             ...bkn..",
+        );
+
+        assert_eq!(Some((Endgame::KXK, Side::White)), classify(krk.material()));
+        assert_eq!(
+            Some((Endgame::KBNK, Side::Black)),
+            classify(kbnk.material())
+        );
+        assert_eq!(None, classify(Board::new().material()));
+        assert_eq!(None, evaluate(&Board::new()));
+    }
+
+    #[test]
+    fn test_evaluate_symmetry() {
+        let boards = [
+            "....k...
+             ........
+             .K......
+             ........
+             ........
+             ........
+             ........
+             .......Q",
+            "........
+             ........
+             ........
+             ........
+             ........
+             ........
+             .K......
+             k.B.N...",
+            "........
+             ........
+             ........
+             ........
+             ....Q...
+             .K......
+             p.......
+             .k......",
+            "K.......
+             ........
+             ........
+             ........
+             ........
+             ......R.
+             ....p...
+             ...k....",
+        ];
+
+        for &diagram in boards.iter() {
+            let board = board(diagram);
+            let score = evaluate_unchecked(&board).unwrap();
+
+            assert_eq!(Some(-score), evaluate_unchecked(&board.color_flipped()));
+        }
+    }
+
+    #[cfg(feature = "symmetry-check")]
+    #[test]
+    fn test_evaluate_symmetry_on_random_boards() {
+        use crate::random::{random_board, Rng};
+
+        let mut evaluated = 0;
+        for seed in 0..2000 {
+            let side = Side::ALL[seed as usize % 2];
+            let pieces = 1 + seed as usize % 3;
+            let board = random_board(&mut Rng::new(seed), side, pieces).unwrap();
+
+            // Debug builds also check this inside evaluate
+            let score = evaluate(&board);
+            assert_eq!(
+                score,
+                evaluate(&board.color_flipped()).map(|flipped| -flipped)
+            );
+            if score.is_some() {
+                evaluated += 1;
+            }
+        }
+
+        assert!(evaluated >= 100, "only {} endgames evaluated", evaluated);
+    }
+
+    #[test]
+    fn test_kxk_drives_king_to_edge() {
+        let cornered = board(
+            "k.......
+             ........
+             .K......
+             ........
+             ........
+             ........
+             ........
+             .......Q",
+        );
+        let centralized = board(
+            "........
+             ........
+             ........
+             ...k....
+             ........
+             .K......
+             ........
+             .......Q",
+        );
+
+        assert!(evaluate(&cornered).unwrap() > evaluate(&centralized).unwrap());
+        assert!(evaluate(&centralized).unwrap() > KNOWN_WIN);
+    }
+
+    #[test]
+    fn test_kbnk_prefers_bishop_corner() {
+        let right_corner = board(
+            "........
+             ........
+             ........
+             ........
+             ........
+             ........
+             .K......
+             k.B.N...",
+        );
+        let wrong_corner = board(
+            "k.......
+             ........
+             .K......
+             ........
+             ........
+             ........
+             ........
+             ..B.N...",
+        );
+
+        assert!(evaluate(&right_corner).unwrap() > evaluate(&wrong_corner).unwrap());
+    }
+
+    #[test]
+    fn test_kqkp() {
+        let rook_pawn = board(
+            "........
+             ........
+             ........
+             ........
+             ....Q...
+             .K......
+             p.......
+             .k......",
+        );
+        let knight_pawn = board(
+            "........
+             ........
+             ........
+             ........
+             ....Q...
+             K.......
+             .p......
+             ..k.....",
+        );
+
+        assert!(evaluate(&knight_pawn).unwrap() > QUEEN_VALUE - PAWN_VALUE);
+        assert!(evaluate(&rook_pawn).unwrap() < PAWN_VALUE * 2);
+    }
+
+    #[test]
+    fn test_krkp() {
+        let king_in_front = board(
+            "........
+             ........
+             ........
+             ........
+             ....p...
+             ........
+             ....K..k
+             R.......",
+        );
+        let pawn_supported = board(
+            "K.......
+             ........
+             ........
+             ........
+             ........
+             ......R.
+             ....p...
+             ...k....",
+        );
+
+        assert_eq!(
+            Some((Endgame::KRKP, Side::White)),
+            classify(king_in_front.material())
+        );
+        assert!(evaluate(&king_in_front).unwrap() > evaluate(&pawn_supported).unwrap());
+        assert!(evaluate(&pawn_supported).unwrap() < ROOK_VALUE / 2);
+    }
+}
@@ -0,0 +1,18 @@
+//! Helpers shared by the unit tests
+
+use crate::board::{BitBoard, Board, Square};
+
+pub fn square(square: &str) -> Square {
+    square.parse().unwrap()
+}
+
+/// Bitboard of the given squares
+pub fn squares(squares: &[&str]) -> BitBoard {
+    squares.iter().fold(BitBoard(0), |bit_board, &square| {
+        bit_board | square.parse().unwrap()
+    })
+}
+
+pub fn board(diagram: &str) -> Board {
+    Board::from_ascii_diagram(diagram).unwrap()
+}
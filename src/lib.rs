@@ -0,0 +1,16 @@
+pub mod analysis;
+pub mod annotation;
+pub mod arena;
+pub mod attacks;
+pub mod board;
+pub mod castling;
+pub mod endgame;
+#[cfg(test)]
+mod fixtures;
+pub mod masks;
+pub mod movegen;
+pub mod random;
+pub mod retrograde;
+pub mod stats;
+pub mod tree;
+pub mod types;
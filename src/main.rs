@@ -1,6 +1,4 @@
-mod board;
-mod movegen;
-mod types;
+use alexander::board;
 
 fn main() {
     println!("No talking! –  GM Ben Finegold");
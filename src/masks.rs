@@ -0,0 +1,176 @@
+use crate::board::{BitBoard, Square};
+use crate::types::*;
+
+const KING_RINGS: [u64; 64] = king_rings();
+
+// Tables below are indexed by side, white then black, and square
+const PAWN_SHIELDS: [[u64; 64]; 2] = forward_zones(2, Files::OwnAndAdjacent);
+const PAWN_STORMS: [[u64; 64]; 2] = forward_zones(4, Files::OwnAndAdjacent);
+const FRONT_SPANS: [[u64; 64]; 2] = forward_zones(7, Files::Own);
+const ATTACK_SPANS: [[u64; 64]; 2] = forward_zones(7, Files::Adjacent);
+const PASSED_PAWN_MASKS: [[u64; 64]; 2] = forward_zones(7, Files::OwnAndAdjacent);
+
+/// Files covered by a forward zone, relative to the square's own file
+enum Files {
+    Own,
+    Adjacent,
+    OwnAndAdjacent,
+}
+
+/// Squares next to a king on the given square
+pub fn king_ring(square: Square) -> BitBoard {
+    BitBoard(KING_RINGS[square.index() as usize])
+}
+
+/// Squares where a side's pawns shield its king: the king's file and the
+/// files beside it, one and two ranks in front of the king
+pub fn pawn_shield(side: Side, king: Square) -> BitBoard {
+    BitBoard(PAWN_SHIELDS[side.index()][king.index() as usize])
+}
+
+/// Squares from which enemy pawns storm a side's king: the king's file and
+/// the files beside it, up to four ranks in front of the king
+pub fn pawn_storm(side: Side, king: Square) -> BitBoard {
+    BitBoard(PAWN_STORMS[side.index()][king.index() as usize])
+}
+
+/// Squares in front of a side's pawn on its own file, up to the last rank
+pub fn front_span(side: Side, square: Square) -> BitBoard {
+    BitBoard(FRONT_SPANS[side.index()][square.index() as usize])
+}
+
+/// Squares a side's pawn could attack as it advances: the files beside it,
+/// from the next rank up to the last
+pub fn attack_span(side: Side, square: Square) -> BitBoard {
+    BitBoard(ATTACK_SPANS[side.index()][square.index() as usize])
+}
+
+/// Squares that must be free of enemy pawns for a side's pawn to be passed
+///
+/// The union of the pawn's front span and attack span.
+pub fn passed_pawn_mask(side: Side, square: Square) -> BitBoard {
+    BitBoard(PASSED_PAWN_MASKS[side.index()][square.index() as usize])
+}
+
+const fn king_rings() -> [u64; 64] {
+    let mut rings = [0; 64];
+    let mut square = 0;
+    while square < 64 {
+        let rank = square as i32 / 8;
+        rings[square] =
+            file_span(square as i32 % 8) & rank_range(rank - 1, rank + 1) & !(1 << square);
+        square += 1;
+    }
+
+    rings
+}
+
+/// Squares on the given files from one to `depth` ranks in front of each
+/// square, for white then black
+const fn forward_zones(depth: i32, files: Files) -> [[u64; 64]; 2] {
+    let mut zones = [[0; 64]; 2];
+    let mut square = 0;
+    while square < 64 {
+        let file = square as i32 % 8;
+        let own_file = 0x0101010101010101 << file;
+        let files = match files {
+            Files::Own => own_file,
+            Files::Adjacent => file_span(file) & !own_file,
+            Files::OwnAndAdjacent => file_span(file),
+        };
+        let rank = square as i32 / 8;
+        zones[0][square] = files & rank_range(rank + 1, rank + depth);
+        zones[1][square] = files & rank_range(rank - depth, rank - 1);
+        square += 1;
+    }
+
+    zones
+}
+
+/// A file and the files beside it
+const fn file_span(file: i32) -> u64 {
+    let mut span = 0;
+    let mut neighbour = file - 1;
+    while neighbour <= file + 1 {
+        if neighbour >= 0 && neighbour < 8 {
+            span |= 0x0101010101010101 << neighbour;
+        }
+        neighbour += 1;
+    }
+
+    span
+}
+
+/// Ranks between two rank indices, inclusive, ignoring those off the board
+const fn rank_range(first: i32, last: i32) -> u64 {
+    let mut range = 0;
+    let mut rank = first;
+    while rank <= last {
+        if rank >= 0 && rank < 8 {
+            range |= 0xff << (rank * 8);
+        }
+        rank += 1;
+    }
+
+    range
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::consts::RANK_4;
+    use crate::fixtures::{square, squares};
+
+    #[test]
+    fn test_king_ring() {
+        assert_eq!(
+            squares(&["d1", "f1", "d2", "e2", "f2"]),
+            king_ring(square("e1"))
+        );
+        assert_eq!(squares(&["b1", "a2", "b2"]), king_ring(square("a1")));
+        assert_eq!(8, king_ring(square("d5")).count());
+    }
+
+    #[test]
+    fn test_pawn_shield() {
+        assert_eq!(
+            squares(&["f2", "g2", "h2", "f3", "g3", "h3"]),
+            pawn_shield(Side::White, square("g1"))
+        );
+        assert_eq!(
+            squares(&["g7", "h7", "g6", "h6"]),
+            pawn_shield(Side::Black, square("h8"))
+        );
+        assert_eq!(BitBoard(0), pawn_shield(Side::White, square("e8")));
+    }
+
+    #[test]
+    fn test_pawn_storm() {
+        let storm = pawn_storm(Side::Black, square("g8"));
+
+        assert_eq!(12, storm.count());
+        assert_eq!(
+            pawn_shield(Side::Black, square("g8")),
+            storm & pawn_shield(Side::Black, square("g8"))
+        );
+        assert_eq!(squares(&["f4", "g4", "h4"]), storm & RANK_4);
+    }
+
+    #[test]
+    fn test_pawn_spans() {
+        assert_eq!(
+            squares(&["e5", "e6", "e7", "e8"]),
+            front_span(Side::White, square("e4"))
+        );
+        assert_eq!(
+            squares(&["a6", "c6", "a5", "c5", "a4", "c4", "a3", "c3", "a2", "c2", "a1", "c1"]),
+            attack_span(Side::Black, square("b7"))
+        );
+        assert_eq!(
+            front_span(Side::White, square("h6")) | attack_span(Side::White, square("h6")),
+            passed_pawn_mask(Side::White, square("h6"))
+        );
+        assert_eq!(4, passed_pawn_mask(Side::White, square("h6")).count());
+        assert_eq!(BitBoard(0), front_span(Side::Black, square("d1")));
+    }
+}
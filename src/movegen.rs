@@ -1,11 +1,13 @@
+use std::convert::TryFrom;
 use std::error::Error;
 
-use crate::board::{BitBoard, Board};
+use crate::board::{BitBoard, Board, Square};
 use crate::types::*;
 
 const CAPTURE_FLAG: u8 = 0x04;
+const PROMOTION_FLAG: u8 = 0x08;
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum MoveType {
     Quiet = 0,
     DoublePawnPush,
@@ -24,11 +26,79 @@ pub enum MoveType {
 }
 
 impl MoveType {
-    fn is_capture(&self) -> bool {
+    /// Every move type, in flag order
+    pub const ALL: [MoveType; 14] = [
+        MoveType::Quiet,
+        MoveType::DoublePawnPush,
+        MoveType::KingsideCastle,
+        MoveType::QueensideCastle,
+        MoveType::Capture,
+        MoveType::EnPassant,
+        MoveType::KnightPromote,
+        MoveType::BishopPromote,
+        MoveType::RookPromote,
+        MoveType::QueenPromote,
+        MoveType::KnightPromoteCapture,
+        MoveType::BishopPromoteCapture,
+        MoveType::RookPromoteCapture,
+        MoveType::QueenPromoteCapture,
+    ];
+
+    pub fn is_capture(&self) -> bool {
         *self as u8 & CAPTURE_FLAG != 0
     }
+
+    /// The piece type a pawn promotes to, if this is a promotion
+    pub fn promotion_type(&self) -> Option<PieceType> {
+        if *self as u8 & PROMOTION_FLAG == 0 {
+            return None;
+        }
+
+        match *self as u8 & 0x03 {
+            0 => Some(PieceType::Knight),
+            1 => Some(PieceType::Bishop),
+            2 => Some(PieceType::Rook),
+            _ => Some(PieceType::Queen),
+        }
+    }
+}
+
+/// Styles of algebraic move notation
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Notation {
+    /// Pieces written as letters, e.g. `Nf3`
+    Algebraic,
+    /// Pieces written as glyphs, e.g. `♘f3`
+    ///
+    /// Both sides use the white glyphs, since the side to move already
+    /// gives the colour.
+    Figurine,
 }
 
+impl Notation {
+    fn piece_symbol(&self, piece: Piece) -> String {
+        match self {
+            Notation::Algebraic => match piece.ptype {
+                PieceType::Pawn => String::new(),
+                _ => piece.to_fen_char().to_ascii_uppercase().to_string(),
+            },
+            Notation::Figurine => Piece {
+                ptype: piece.ptype,
+                side: Side::White,
+            }
+            .to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for MoveType {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(*u.choose(&MoveType::ALL)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Move {
     piece: Piece,
     origin: String,
@@ -37,38 +107,230 @@ pub struct Move {
 }
 
 impl Move {
-    fn is_capture(&self) -> bool {
+    /// Makes a move between two squares given in algebraic notation
+    pub fn new(
+        piece: Piece,
+        origin: &str,
+        target: &str,
+        move_type: MoveType,
+    ) -> Result<Move, InvalidSquareError> {
+        Ok(Move {
+            piece,
+            origin: Square::try_from(origin)?.to_string(),
+            target: Square::try_from(target)?.to_string(),
+            move_type,
+        })
+    }
+
+    pub fn piece(&self) -> Piece {
+        self.piece
+    }
+
+    pub fn origin(&self) -> &str {
+        &self.origin
+    }
+
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    pub fn move_type(&self) -> MoveType {
+        self.move_type
+    }
+
+    pub fn is_capture(&self) -> bool {
         self.move_type.is_capture()
     }
 
-    fn apply(&self, board: &mut Board) -> Result<(), Box<dyn Error>> {
+    /// Writes the move in short algebraic notation
+    ///
+    /// Disambiguation and check suffixes are not written, since they
+    /// depend on the rest of the position.
+    pub fn to_notation(&self, notation: Notation) -> String {
+        match self.move_type {
+            MoveType::KingsideCastle => return "O-O".to_string(),
+            MoveType::QueensideCastle => return "O-O-O".to_string(),
+            _ => (),
+        }
+
+        let mut move_str = String::new();
+        if self.piece.ptype == PieceType::Pawn {
+            if self.is_capture() {
+                move_str.extend(self.origin.chars().next());
+            }
+        } else {
+            move_str += &notation.piece_symbol(self.piece);
+        }
+
+        if self.is_capture() {
+            move_str.push('x');
+        }
+        move_str += &self.target;
+
+        if let Some(ptype) = self.move_type.promotion_type() {
+            move_str.push('=');
+            move_str += &notation.piece_symbol(Piece {
+                ptype,
+                side: self.piece.side,
+            });
+        }
+
+        move_str
+    }
+
+    pub fn apply(&self, board: &mut Board) -> Result<(), Box<dyn Error>> {
         match self.move_type {
             MoveType::Quiet | MoveType::DoublePawnPush => {
                 let piece_bb = board.bit_board_for(self.piece);
-                let move_bb = BitBoard::from(self.origin.as_str())
-                    ^ BitBoard::from(self.target.as_str());
+                let move_bb = BitBoard::try_from(self.origin.as_str())?
+                    ^ BitBoard::try_from(self.target.as_str())?;
                 board.set_bit_board(piece_bb ^ move_bb, self.piece);
                 board.set_square(self.origin.as_str(), None)?;
                 board.set_square(self.target.as_str(), Some(self.piece))?;
-            },
-            MoveType::KingsideCastle => {
-            },
+            }
+            MoveType::KingsideCastle => {}
             _ => (),
         };
         Ok(())
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Move {
+    /// Generates a move between valid squares, which need not be legal on
+    /// any board
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let origin: Square = u.arbitrary()?;
+        let target: Square = u.arbitrary()?;
+
+        Ok(Move::new(
+            u.arbitrary()?,
+            &origin.to_string(),
+            &target.to_string(),
+            u.arbitrary()?,
+        )
+        .unwrap())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_is_capture() {
-        assert_eq!(false, MoveType::Quiet.is_capture());
-        assert_eq!(false, MoveType::DoublePawnPush.is_capture());
-        assert_eq!(true, MoveType::Capture.is_capture());
-        assert_eq!(true, MoveType::KnightPromoteCapture.is_capture());
+        assert!(!MoveType::Quiet.is_capture());
+        assert!(!MoveType::DoublePawnPush.is_capture());
+        assert!(MoveType::Capture.is_capture());
+        assert!(MoveType::KnightPromoteCapture.is_capture());
+    }
+
+    #[test]
+    fn test_promotion_type() {
+        assert_eq!(None, MoveType::Capture.promotion_type());
+        assert_eq!(
+            Some(PieceType::Knight),
+            MoveType::KnightPromote.promotion_type()
+        );
+        assert_eq!(
+            Some(PieceType::Queen),
+            MoveType::QueenPromoteCapture.promotion_type()
+        );
+    }
+
+    #[test]
+    fn test_to_notation() {
+        let knight_move = Move {
+            piece: Piece {
+                side: Side::White,
+                ptype: PieceType::Knight,
+            },
+            origin: "g1".to_string(),
+            target: "f3".to_string(),
+            move_type: MoveType::Quiet,
+        };
+        let pawn_capture = Move {
+            piece: Piece {
+                side: Side::Black,
+                ptype: PieceType::Pawn,
+            },
+            origin: "e5".to_string(),
+            target: "d4".to_string(),
+            move_type: MoveType::Capture,
+        };
+        let promotion = Move {
+            piece: Piece {
+                side: Side::White,
+                ptype: PieceType::Pawn,
+            },
+            origin: "b7".to_string(),
+            target: "a8".to_string(),
+            move_type: MoveType::QueenPromoteCapture,
+        };
+        let castle = Move {
+            piece: Piece {
+                side: Side::Black,
+                ptype: PieceType::King,
+            },
+            origin: "e8".to_string(),
+            target: "c8".to_string(),
+            move_type: MoveType::QueensideCastle,
+        };
+
+        assert_eq!("Nf3", knight_move.to_notation(Notation::Algebraic));
+        assert_eq!("♘f3", knight_move.to_notation(Notation::Figurine));
+        assert_eq!("exd4", pawn_capture.to_notation(Notation::Figurine));
+        assert_eq!("bxa8=Q", promotion.to_notation(Notation::Algebraic));
+        assert_eq!("bxa8=♕", promotion.to_notation(Notation::Figurine));
+        assert_eq!("O-O-O", castle.to_notation(Notation::Figurine));
+        assert_eq!(
+            "♗b4",
+            Move::new(
+                Piece {
+                    side: Side::Black,
+                    ptype: PieceType::Bishop,
+                },
+                "f8",
+                "b4",
+                MoveType::Quiet
+            )
+            .unwrap()
+            .to_notation(Notation::Figurine)
+        );
+    }
+
+    #[test]
+    fn test_new_rejects_bad_squares() {
+        let pawn = Piece {
+            side: Side::White,
+            ptype: PieceType::Pawn,
+        };
+
+        assert!(Move::new(pawn, "", "e4", MoveType::Capture).is_err());
+        assert!(Move::new(pawn, "é2", "e4", MoveType::Quiet).is_err());
+        assert!(Move::new(pawn, "e2", "e9", MoveType::Quiet).is_err());
+        assert_eq!(
+            "dxe5",
+            Move::new(pawn, "D4", "e5", MoveType::Capture)
+                .unwrap()
+                .to_notation(Notation::Algebraic)
+        );
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary_move() {
+        use arbitrary::{Arbitrary, Unstructured};
+        use std::convert::TryFrom;
+
+        let bytes: Vec<u8> = (0..=255).rev().collect();
+        let mut u = Unstructured::new(&bytes);
+        for _ in 0..32 {
+            let generated = Move::arbitrary(&mut u).unwrap();
+
+            assert!(crate::board::Square::try_from(generated.origin.as_str()).is_ok());
+            assert!(crate::board::Square::try_from(generated.target.as_str()).is_ok());
+        }
     }
 
     #[test]
@@ -79,7 +341,7 @@ mod tests {
             ptype: PieceType::Knight,
         };
         let quiet_move = Move {
-            piece: piece,
+            piece,
             origin: "b1".to_string(),
             target: "c3".to_string(),
             move_type: MoveType::Quiet,
@@ -88,7 +350,7 @@ mod tests {
         assert!(quiet_move.apply(&mut board).is_ok());
 
         assert_eq!(
-            BitBoard::from("c3") ^ BitBoard::from("g1"),
+            BitBoard::try_from("c3").unwrap() ^ BitBoard::try_from("g1").unwrap(),
             board.bit_board_for(piece)
         );
         assert_eq!(
@@ -98,8 +360,8 @@ mod tests {
                 ptype: PieceType::Pawn
             })
         );
-        assert_eq!(Some(piece), board.get_square("c3"));
-        assert_eq!(None, board.get_square("b1"));
+        assert_eq!(Some(piece), board.get_square("c3").unwrap());
+        assert_eq!(None, board.get_square("b1").unwrap());
     }
 
     #[test]
@@ -110,7 +372,7 @@ mod tests {
             ptype: PieceType::Pawn,
         };
         let double_pawn_push = Move {
-            piece: piece,
+            piece,
             origin: "c7".to_string(),
             target: "c5".to_string(),
             move_type: MoveType::DoublePawnPush,
@@ -119,14 +381,14 @@ mod tests {
         assert!(double_pawn_push.apply(&mut board).is_ok());
 
         assert_eq!(
-            BitBoard::from("b1") ^ BitBoard::from("g1"),
+            BitBoard::try_from("b1").unwrap() ^ BitBoard::try_from("g1").unwrap(),
             board.bit_board_for(Piece {
                 side: Side::White,
                 ptype: PieceType::Knight
             })
         );
         assert_eq!(BitBoard(0x00fb000400000000), board.bit_board_for(piece));
-        assert_eq!(Some(piece), board.get_square("c5"));
-        assert_eq!(None, board.get_square("c7"));
+        assert_eq!(Some(piece), board.get_square("c5").unwrap());
+        assert_eq!(None, board.get_square("c7").unwrap());
     }
 }
@@ -0,0 +1,210 @@
+use crate::attacks::attackers_of;
+use crate::board::consts::{DARK_SQUARES, FILES, LIGHT_SQUARES, RANK_1, RANK_8};
+use crate::board::{_8x8Board, BitBoard, Board, Square};
+use crate::types::*;
+
+/// Small seedable pseudo-random number generator (SplitMix64)
+///
+/// Not suitable for anything security related, but fast and reproducible
+/// across platforms, which is what tests and benchmarks need.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+
+        z ^ (z >> 31)
+    }
+
+    /// Uniform number from 0 up to, but not including, `bound`
+    pub fn below(&mut self, bound: u64) -> u64 {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+}
+
+/// Places two kings and a number of other random pieces on the board,
+/// retrying until the side not to move is not in check
+///
+/// The other pieces are drawn from what remains of both starting sets, so
+/// `None` is returned for more than 30. Pawns stay on their starting files
+/// with the white pawn below the black one, and bishops stay on their
+/// starting colours, so the board always passes the retrograde checks.
+/// Placement starts over if a piece finds all of its squares taken.
+pub fn random_board(rng: &mut Rng, side_to_move: Side, pieces: usize) -> Option<Board> {
+    if pieces > 30 {
+        return None;
+    }
+
+    loop {
+        let board = match place_pieces(rng, pieces) {
+            Some(board) => board,
+            None => continue,
+        };
+        let waiting_king = board.bit_board_for(Piece {
+            ptype: PieceType::King,
+            side: side_to_move.opposite(),
+        });
+        let waiting_king = waiting_king.squares().next().unwrap();
+
+        if attackers_of(&board, waiting_king, side_to_move).0 == 0 {
+            return Some(board);
+        }
+    }
+}
+
+/// Places the pieces, or returns `None` if one of them has nowhere to go
+fn place_pieces(rng: &mut Rng, pieces: usize) -> Option<Board> {
+    let mut squares = _8x8Board::empty();
+    let mut occupied = BitBoard(0);
+    let mut pawns = [BitBoard(0); 2];
+
+    let mut place = |rng: &mut Rng, piece: Piece, allowed: BitBoard| {
+        let free: Vec<Square> = (allowed & !occupied).squares().collect();
+        if free.is_empty() {
+            return None;
+        }
+        let square = free[rng.below(free.len() as u64) as usize];
+
+        occupied = occupied | BitBoard::from(square);
+        squares
+            .set_square(&square.to_string(), Some(piece))
+            .unwrap();
+        Some(square)
+    };
+
+    // Each piece with the squares it may stand on
+    let mut pool = Vec::new();
+    for &side in Side::ALL.iter() {
+        let piece = |ptype| Piece { ptype, side };
+        for &file in FILES.iter() {
+            pool.push((piece(PieceType::Pawn), file & !(RANK_1 | RANK_8)));
+        }
+        pool.push((piece(PieceType::Bishop), LIGHT_SQUARES));
+        pool.push((piece(PieceType::Bishop), DARK_SQUARES));
+        for &ptype in [
+            PieceType::Knight,
+            PieceType::Knight,
+            PieceType::Rook,
+            PieceType::Rook,
+            PieceType::Queen,
+        ]
+        .iter()
+        {
+            pool.push((piece(ptype), BitBoard(!0)));
+        }
+    }
+
+    for &side in Side::ALL.iter() {
+        let king = Piece {
+            ptype: PieceType::King,
+            side,
+        };
+        place(rng, king, BitBoard(!0))?;
+    }
+    for _ in 0..pieces {
+        let (piece, mut allowed) = pool.swap_remove(rng.below(pool.len() as u64) as usize);
+        if piece.ptype == PieceType::Pawn {
+            allowed = behind_pawns(piece.side, allowed, pawns[piece.side.opposite().index()]);
+        }
+        let square = place(rng, piece, allowed)?;
+        if piece.ptype == PieceType::Pawn {
+            pawns[piece.side.index()] = pawns[piece.side.index()] | BitBoard::from(square);
+        }
+    }
+
+    Some(Board::from(squares))
+}
+
+/// Squares a pawn may take on its file without having passed the other
+/// side's pawn there, which no game can reach without a capture
+fn behind_pawns(side: Side, file: BitBoard, opposing_pawns: BitBoard) -> BitBoard {
+    let rank = |square: Square| side.relative_rank(square.rank_index());
+
+    match (file & opposing_pawns).squares().next() {
+        Some(opposing) => file
+            .squares()
+            .filter(|&square| rank(square) < rank(opposing))
+            .fold(BitBoard(0), |squares, square| {
+                squares | BitBoard::from(square)
+            }),
+        None => file,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::retrograde;
+
+    #[test]
+    fn test_rng() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+            assert!(a.below(10) < 10);
+            b.below(10);
+        }
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+
+    #[test]
+    fn test_random_board() {
+        let mut rng = Rng::new(42);
+
+        for &pieces in [0, 1, 5, 12, 30].iter() {
+            let board = random_board(&mut rng, Side::White, pieces).unwrap();
+            let black_king = board
+                .bit_board_for(Piece {
+                    ptype: PieceType::King,
+                    side: Side::Black,
+                })
+                .squares()
+                .next()
+                .unwrap();
+
+            assert_eq!(pieces as u32 + 2, board.occupied().count());
+            assert!(retrograde::is_reachable(&board));
+            assert_eq!(0, attackers_of(&board, black_king, Side::White).0);
+        }
+        assert_eq!(
+            random_board(&mut Rng::new(3), Side::Black, 8)
+                .unwrap()
+                .render(&Default::default()),
+            random_board(&mut Rng::new(3), Side::Black, 8)
+                .unwrap()
+                .render(&Default::default())
+        );
+        assert!(random_board(&mut rng, Side::White, 31).is_none());
+    }
+
+    #[test]
+    fn test_full_random_boards() {
+        for seed in 0..200 {
+            let board = random_board(&mut Rng::new(seed), Side::Black, 30).unwrap();
+
+            assert_eq!(32, board.occupied().count());
+            assert!(retrograde::is_reachable(&board));
+            for file in FILES.iter() {
+                let pawn_rank = |side| {
+                    let pawn = Piece {
+                        ptype: PieceType::Pawn,
+                        side,
+                    };
+                    let square = (board.bit_board_for(pawn) & *file).squares().next();
+                    square.unwrap().rank_index()
+                };
+                assert!(pawn_rank(Side::White) < pawn_rank(Side::Black));
+            }
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use crate::board::consts::{DARK_SQUARES, RANK_1, RANK_8};
+use crate::board::{Board, Square};
+use crate::types::*;
+
+/// Reasons a board cannot arise from the starting position
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// A side does not have exactly one king
+    KingCount { side: Side, count: u32 },
+    /// A pawn stands on the first or eighth rank
+    PawnOnBackRank { square: Square },
+    /// A side has more than eight pawns
+    TooManyPawns { side: Side, count: u32 },
+    /// A side has more pieces beyond its starting set than it has lost pawns
+    /// to promote
+    TooManyPromotions {
+        side: Side,
+        promoted: u32,
+        missing_pawns: u32,
+    },
+    /// A side's pawns have changed files more often than they could have
+    /// captured enemy pieces
+    TooManyPawnCaptures {
+        side: Side,
+        required: u32,
+        captured: u32,
+    },
+}
+
+/// Whether a board passes every retrograde check
+///
+/// The checks only count material and pawn files, so passing them is
+/// necessary but not sufficient for the board to be reachable.
+pub fn is_reachable(board: &Board) -> bool {
+    violations(board).is_empty()
+}
+
+/// Every retrograde check the board fails
+pub fn violations(board: &Board) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    let pawns = board.bit_board_for(Piece {
+        ptype: PieceType::Pawn,
+        side: Side::White,
+    }) | board.bit_board_for(Piece {
+        ptype: PieceType::Pawn,
+        side: Side::Black,
+    });
+    for square in (pawns & (RANK_1 | RANK_8)).squares() {
+        violations.push(Violation::PawnOnBackRank { square });
+    }
+
+    for &side in Side::ALL.iter() {
+        let count = |ptype| board.bit_board_for(Piece { ptype, side }).count();
+
+        if count(PieceType::King) != 1 {
+            violations.push(Violation::KingCount {
+                side,
+                count: count(PieceType::King),
+            });
+        }
+
+        let pawn_count = count(PieceType::Pawn);
+        if pawn_count > 8 {
+            violations.push(Violation::TooManyPawns {
+                side,
+                count: pawn_count,
+            });
+        }
+
+        let promoted = promoted_pieces(board, side);
+        let missing_pawns = 8u32.saturating_sub(pawn_count);
+        if promoted > missing_pawns {
+            violations.push(Violation::TooManyPromotions {
+                side,
+                promoted,
+                missing_pawns,
+            });
+        }
+
+        let required = pawn_captures(board, side);
+        let captured = 16u32.saturating_sub(board.occupancy(side.opposite()).count());
+        if required > captured {
+            violations.push(Violation::TooManyPawnCaptures {
+                side,
+                required,
+                captured,
+            });
+        }
+    }
+
+    violations
+}
+
+/// Least number of a side's pieces that must have come from promotions
+///
+/// Bishops are counted per square colour, since a side starts with one of
+/// each.
+fn promoted_pieces(board: &Board, side: Side) -> u32 {
+    let count = |ptype| board.bit_board_for(Piece { ptype, side });
+    let bishops = count(PieceType::Bishop);
+    let dark_bishops = (bishops & DARK_SQUARES).count();
+    let light_bishops = bishops.count() - dark_bishops;
+
+    count(PieceType::Knight).count().saturating_sub(2)
+        + dark_bishops.saturating_sub(1)
+        + light_bishops.saturating_sub(1)
+        + count(PieceType::Rook).count().saturating_sub(2)
+        + count(PieceType::Queen).count().saturating_sub(1)
+}
+
+/// Least number of captures a side's pawns need to reach their files
+///
+/// Each pawn starts on its own file and moves one file per capture, so
+/// this is the cheapest way to match the pawns' files to distinct starting
+/// files. Matching sorted files in order is optimal.
+fn pawn_captures(board: &Board, side: Side) -> u32 {
+    let mut files: Vec<u32> = board
+        .bit_board_for(Piece {
+            ptype: PieceType::Pawn,
+            side,
+        })
+        .squares()
+        .map(|square| square.file_index() as u32)
+        .collect();
+    if files.len() > 8 {
+        return 0;
+    }
+    files.sort_unstable();
+
+    // cost[j] is the cheapest matching of the pawns so far to the first j
+    // starting files
+    let mut cost = [0u32; 9];
+    for (i, &file) in files.iter().enumerate() {
+        let mut next = [u32::MAX; 9];
+        for start_file in i..8 {
+            let matched =
+                cost[start_file].saturating_add((file as i32 - start_file as i32).unsigned_abs());
+            next[start_file + 1] = next[start_file].min(matched);
+        }
+        cost = next;
+    }
+
+    cost[files.len()..].iter().copied().min().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::board;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_starting_position() {
+        assert!(is_reachable(&Board::new()));
+    }
+
+    #[test]
+    fn test_kings_and_pawns() {
+        let board = board(
+            "....k..P
+             ........
+             ........
+             ........
+             ........
+             ........
+             ........
+             ........",
+        );
+
+        assert_eq!(
+            vec![
+                Violation::PawnOnBackRank {
+                    square: Square::try_from("h8").unwrap()
+                },
+                Violation::KingCount {
+                    side: Side::White,
+                    count: 0
+                },
+            ],
+            violations(&board)
+        );
+    }
+
+    #[test]
+    fn test_promotions() {
+        let three_queens = board(
+            "rnbqkbnr
+             pppppppp
+             ........
+             ........
+             ........
+             ..Q..Q..
+             PPPPPPP.
+             RNBQKBNR",
+        );
+        let same_colour_bishops = board(
+            "....k...
+             ........
+             ........
+             ........
+             ........
+             ........
+             ........
+             ..B.KB.B",
+        );
+
+        assert_eq!(
+            vec![Violation::TooManyPromotions {
+                side: Side::White,
+                promoted: 2,
+                missing_pawns: 1,
+            }],
+            violations(&three_queens)
+        );
+        assert!(is_reachable(&same_colour_bishops));
+        assert_eq!(1, promoted_pieces(&same_colour_bishops, Side::White));
+    }
+
+    #[test]
+    fn test_pawn_captures() {
+        let doubled = board(
+            "....k...
+             ........
+             ........
+             ........
+             ....P...
+             ....P...
+             ....P...
+             ....K...",
+        );
+        let full_black_army = board(
+            "rnbqkbnr
+             pppppppp
+             ........
+             ........
+             ........
+             ....P...
+             PPPPP..P
+             RNBQKBNR",
+        );
+
+        assert_eq!(2, pawn_captures(&doubled, Side::White));
+        assert!(is_reachable(&doubled));
+        assert_eq!(
+            vec![Violation::TooManyPawnCaptures {
+                side: Side::White,
+                required: 1,
+                captured: 0,
+            }],
+            violations(&full_black_army)
+        );
+    }
+}
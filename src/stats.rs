@@ -0,0 +1,242 @@
+use std::f64::consts::{LN_10, SQRT_2};
+
+/// Two-sided 95% quantile of the standard normal distribution
+const Z_95: f64 = 1.959_963_984_540_054;
+
+/// Win, draw and loss counts of a match, from the first player's point of
+/// view
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Wdl {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+/// Counts of game pair results, indexed by the pair's total score in half
+/// points: `[LL, LD, LW or DD, DW, WW]`
+///
+/// Pairs play the same opening with colours reversed, so their results are
+/// less noisy than single games.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Pentanomial(pub [u32; 5]);
+
+/// Strength difference estimated from a match
+///
+/// Elo values are from the first player's point of view.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EloEstimate {
+    pub elo: f64,
+    /// Lower bound of the 95% confidence interval
+    pub lower: f64,
+    /// Upper bound of the 95% confidence interval
+    pub upper: f64,
+    /// Likelihood of superiority, the probability that the first player is
+    /// the stronger one
+    pub los: f64,
+    /// Elo scaled by the spread of the results, which makes tests with
+    /// different draw rates comparable
+    pub normalized: f64,
+}
+
+impl Wdl {
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Mean score per game, with draws worth half a point, or `None` when
+    /// no games were played
+    pub fn score(&self) -> Option<f64> {
+        if self.games() == 0 {
+            return None;
+        }
+
+        Some((self.wins as f64 + self.draws as f64 / 2.0) / self.games() as f64)
+    }
+
+    /// Estimates the strength difference, see `estimate` for when this is
+    /// `None`
+    pub fn elo(&self) -> Option<EloEstimate> {
+        let outcomes = [(1.0, self.wins), (0.5, self.draws), (0.0, self.losses)];
+
+        estimate(&outcomes, 1.0)
+    }
+}
+
+impl Pentanomial {
+    pub fn pairs(&self) -> u32 {
+        self.0.iter().sum()
+    }
+
+    /// Mean score per game, or `None` when no pairs were played
+    pub fn score(&self) -> Option<f64> {
+        if self.pairs() == 0 {
+            return None;
+        }
+
+        let points: u32 = self
+            .0
+            .iter()
+            .zip(0..)
+            .map(|(&count, half)| count * half)
+            .sum();
+
+        Some(points as f64 / 4.0 / self.pairs() as f64)
+    }
+
+    /// Estimates the strength difference, see `estimate` for when this is
+    /// `None`
+    pub fn elo(&self) -> Option<EloEstimate> {
+        let mut outcomes = [(0.0, 0); 5];
+        for (half_points, outcome) in outcomes.iter_mut().enumerate() {
+            *outcome = (half_points as f64 / 4.0, self.0[half_points]);
+        }
+
+        estimate(&outcomes, 2.0)
+    }
+}
+
+/// Elo difference corresponding to a mean score under the logistic model
+pub fn elo_from_score(score: f64) -> f64 {
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+/// Estimates Elo from the counts of each outcome's score, where each outcome
+/// is the mean over a sample of `games_per_sample` games
+///
+/// Returns `None` when there are no samples, or when every sample has the
+/// same score, since the error margin and likelihood of superiority are
+/// then undefined.
+fn estimate(outcomes: &[(f64, u32)], games_per_sample: f64) -> Option<EloEstimate> {
+    let samples: u32 = outcomes.iter().map(|&(_, count)| count).sum();
+    if outcomes.iter().filter(|&&(_, count)| count > 0).count() < 2 {
+        return None;
+    }
+    let samples = samples as f64;
+    let score = outcomes
+        .iter()
+        .map(|&(value, count)| value * count as f64)
+        .sum::<f64>()
+        / samples;
+    let variance = outcomes
+        .iter()
+        .map(|&(value, count)| (value - score).powi(2) * count as f64)
+        .sum::<f64>()
+        / samples;
+    let error = (variance / samples).sqrt();
+    let game_deviation = (variance * games_per_sample).sqrt();
+
+    Some(EloEstimate {
+        elo: elo_from_score(score),
+        lower: elo_from_score((score - Z_95 * error).max(0.0)),
+        upper: elo_from_score((score + Z_95 * error).min(1.0)),
+        los: normal_cdf((score - 0.5) / error),
+        normalized: (score - 0.5) / game_deviation * 800.0 / LN_10,
+    })
+}
+
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / SQRT_2))
+}
+
+/// Error function, accurate to about 1e-7
+///
+/// Uses the rational approximation 7.1.26 from Abramowitz and Stegun.
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let polynomial = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let y = 1.0 - polynomial * (-x * x).exp();
+
+    if x < 0.0 {
+        -y
+    } else {
+        y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() < 0.01,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_even_match() {
+        let estimate = Wdl {
+            wins: 30,
+            draws: 40,
+            losses: 30,
+        }
+        .elo()
+        .unwrap();
+
+        assert_close(0.0, estimate.elo);
+        assert_close(0.5, estimate.los);
+        assert_close(0.0, estimate.normalized);
+        assert_close(-estimate.lower, estimate.upper);
+    }
+
+    #[test]
+    fn test_wdl_elo() {
+        let wdl = Wdl {
+            wins: 60,
+            draws: 0,
+            losses: 40,
+        };
+        let estimate = wdl.elo().unwrap();
+
+        assert_eq!(100, wdl.games());
+        assert_close(0.6, wdl.score().unwrap());
+        assert_close(70.44, estimate.elo);
+        assert_close(0.979, estimate.los);
+        assert!(estimate.lower < estimate.elo && estimate.elo < estimate.upper);
+        assert!(estimate.lower > 0.0);
+        assert_close(70.92, estimate.normalized);
+    }
+
+    #[test]
+    fn test_pentanomial_elo() {
+        let pentanomial = Pentanomial([5, 20, 40, 25, 10]);
+        let estimate = pentanomial.elo().unwrap();
+
+        assert_eq!(100, pentanomial.pairs());
+        assert_close(0.5375, pentanomial.score().unwrap());
+        assert_close(elo_from_score(0.5375), estimate.elo);
+        assert!(estimate.los > 0.9);
+        assert!(estimate.lower < estimate.elo && estimate.elo < estimate.upper);
+    }
+
+    #[test]
+    fn test_undefined_estimates() {
+        let all_draws = Wdl {
+            wins: 0,
+            draws: 20,
+            losses: 0,
+        };
+
+        assert_eq!(None, Wdl::default().score());
+        assert_eq!(None, Wdl::default().elo());
+        assert_eq!(None, Pentanomial::default().score());
+        assert_eq!(None, Pentanomial::default().elo());
+        assert_eq!(Some(0.5), all_draws.score());
+        assert_eq!(None, all_draws.elo());
+        assert_eq!(None, Pentanomial([0, 0, 0, 0, 12]).elo());
+        assert!(Pentanomial([0, 0, 3, 0, 12]).elo().is_some());
+    }
+
+    #[test]
+    fn test_elo_from_score() {
+        assert_close(0.0, elo_from_score(0.5));
+        assert_close(-190.85, elo_from_score(0.25));
+        assert_eq!(f64::INFINITY, elo_from_score(1.0));
+    }
+}
@@ -0,0 +1,365 @@
+use crate::annotation::Nag;
+use crate::arena::{Arena, Index};
+use crate::movegen::Move;
+use crate::types::InvalidNodeError;
+
+/// Handle to a node of a `GameTree`
+pub type NodeId = Index;
+
+struct Node {
+    /// Move leading to this node, absent only at the root
+    played: Option<Move>,
+    parent: Option<NodeId>,
+    /// Continuations from this node, the mainline first
+    children: Vec<NodeId>,
+    nags: Vec<Nag>,
+}
+
+/// Tree of a game's moves and its variations
+///
+/// The root stands for the starting position. Each other node is the move
+/// leading to its position, and a node's first child continues the line it
+/// belongs to while later children are alternative variations.
+pub struct GameTree {
+    nodes: Arena<Node>,
+    root: NodeId,
+}
+
+impl GameTree {
+    /// Creates a tree with no moves
+    pub fn new() -> GameTree {
+        let mut nodes = Arena::new();
+        let root = nodes.insert(Node {
+            played: None,
+            parent: None,
+            children: Vec::new(),
+            nags: Vec::new(),
+        });
+
+        GameTree { nodes, root }
+    }
+
+    pub fn root(&self) -> NodeId {
+        self.root
+    }
+
+    /// Number of moves in the tree
+    pub fn len(&self) -> usize {
+        self.nodes.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds a move after a node, as a new variation if the node already has
+    /// a continuation
+    ///
+    /// If the move is already a continuation of the node, its existing
+    /// node is returned instead.
+    pub fn add_move(&mut self, parent: NodeId, played: Move) -> Result<NodeId, InvalidNodeError> {
+        let existing = self
+            .node(parent)?
+            .children
+            .iter()
+            .find(|&&child| self.nodes.get(child).unwrap().played.as_ref() == Some(&played));
+        if let Some(&child) = existing {
+            return Ok(child);
+        }
+
+        let child = self.nodes.insert(Node {
+            played: Some(played),
+            parent: Some(parent),
+            children: Vec::new(),
+            nags: Vec::new(),
+        });
+        self.nodes.get_mut(parent).unwrap().children.push(child);
+
+        Ok(child)
+    }
+
+    /// Move leading to a node, or `None` for the root and removed nodes
+    pub fn played(&self, node: NodeId) -> Option<&Move> {
+        self.nodes.get(node)?.played.as_ref()
+    }
+
+    pub fn parent(&self, node: NodeId) -> Option<NodeId> {
+        self.nodes.get(node)?.parent
+    }
+
+    /// Continuations of a node, the mainline first
+    pub fn children(&self, node: NodeId) -> &[NodeId] {
+        self.nodes
+            .get(node)
+            .map_or(&[], |node| node.children.as_slice())
+    }
+
+    /// Whether a node continues its parent's line rather than starting a
+    /// variation
+    pub fn is_mainline_child(&self, node: NodeId) -> bool {
+        self.parent(node)
+            .is_some_and(|parent| self.children(parent)[0] == node)
+    }
+
+    /// Attaches an annotation to a node, ignoring repeats
+    ///
+    /// Annotations on the root describe the starting position.
+    pub fn annotate<N: Into<Nag>>(&mut self, node: NodeId, nag: N) -> Result<(), InvalidNodeError> {
+        self.node(node)?;
+        let nags = &mut self.nodes.get_mut(node).unwrap().nags;
+        let nag = nag.into();
+        if !nags.contains(&nag) {
+            nags.push(nag);
+        }
+
+        Ok(())
+    }
+
+    /// Annotations of a node in the order they were added
+    pub fn nags(&self, node: NodeId) -> &[Nag] {
+        self.nodes
+            .get(node)
+            .map_or(&[], |node| node.nags.as_slice())
+    }
+
+    pub fn clear_nags(&mut self, node: NodeId) -> Result<(), InvalidNodeError> {
+        self.node(node)?;
+        self.nodes.get_mut(node).unwrap().nags.clear();
+
+        Ok(())
+    }
+
+    /// Nodes of the line that follows first continuations from a node
+    pub fn mainline(&self, from: NodeId) -> Vec<NodeId> {
+        let mut line = Vec::new();
+        let mut node = from;
+        while let Some(&child) = self.children(node).first() {
+            line.push(child);
+            node = child;
+        }
+
+        line
+    }
+
+    /// Moves a variation one place up among its siblings
+    ///
+    /// Promoting the first variation makes it the mainline. Does nothing to
+    /// a node that is already first.
+    pub fn promote(&mut self, node: NodeId) -> Result<(), InvalidNodeError> {
+        self.shift_among_siblings(node, |position, _| position.saturating_sub(1))
+    }
+
+    /// Moves a variation one place down among its siblings
+    pub fn demote(&mut self, node: NodeId) -> Result<(), InvalidNodeError> {
+        self.shift_among_siblings(node, |position, siblings| (position + 1).min(siblings - 1))
+    }
+
+    /// Makes a variation the mainline of its parent
+    pub fn promote_to_mainline(&mut self, node: NodeId) -> Result<(), InvalidNodeError> {
+        self.shift_among_siblings(node, |_, _| 0)
+    }
+
+    /// Deletes a node along with every move after it
+    pub fn remove(&mut self, node: NodeId) -> Result<(), InvalidNodeError> {
+        let parent = self.sibling_list_of(node)?;
+        self.nodes
+            .get_mut(parent)
+            .unwrap()
+            .children
+            .retain(|&child| child != node);
+
+        let mut removed = vec![node];
+        while let Some(next) = removed.pop() {
+            if let Some(node) = self.nodes.remove(next) {
+                removed.extend(node.children);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Nodes after a node in depth-first order, each line before the
+    /// variations that branch off it
+    pub fn depth_first(&self, from: NodeId) -> DepthFirst<'_> {
+        DepthFirst {
+            tree: self,
+            stack: self.children(from).iter().rev().copied().collect(),
+        }
+    }
+
+    fn node(&self, node: NodeId) -> Result<&Node, InvalidNodeError> {
+        self.nodes.get(node).ok_or_else(|| InvalidNodeError {
+            msg: format!("{:?} is not in the tree", node),
+        })
+    }
+
+    /// Parent of a node that is allowed to move among its siblings
+    fn sibling_list_of(&self, node: NodeId) -> Result<NodeId, InvalidNodeError> {
+        self.node(node)?.parent.ok_or_else(|| InvalidNodeError {
+            msg: "the root cannot be moved or removed".to_string(),
+        })
+    }
+
+    fn shift_among_siblings<F>(
+        &mut self,
+        node: NodeId,
+        new_position: F,
+    ) -> Result<(), InvalidNodeError>
+    where
+        F: Fn(usize, usize) -> usize,
+    {
+        let parent = self.sibling_list_of(node)?;
+        let siblings = &mut self.nodes.get_mut(parent).unwrap().children;
+        let position = siblings.iter().position(|&child| child == node).unwrap();
+        let moved = siblings.remove(position);
+        siblings.insert(new_position(position, siblings.len() + 1), moved);
+
+        Ok(())
+    }
+}
+
+impl Default for GameTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Iterator over the nodes of a `GameTree`, see `GameTree::depth_first`
+pub struct DepthFirst<'a> {
+    tree: &'a GameTree,
+    stack: Vec<NodeId>,
+}
+
+impl<'a> Iterator for DepthFirst<'a> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let node = self.stack.pop()?;
+        self.stack
+            .extend(self.tree.children(node).iter().rev().copied());
+
+        Some(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::annotation::Annotation;
+    use crate::movegen::MoveType;
+    use crate::types::*;
+
+    fn pawn_push(side: Side, origin: &str, target: &str) -> Move {
+        let piece = Piece {
+            ptype: PieceType::Pawn,
+            side,
+        };
+
+        Move::new(piece, origin, target, MoveType::Quiet).unwrap()
+    }
+
+    /// 1. e4 e5 (1... c5 2. Nf3) (1... e6) 2. d4
+    fn sample_tree() -> (GameTree, [NodeId; 6]) {
+        let mut tree = GameTree::new();
+        let root = tree.root();
+        let e4 = tree
+            .add_move(root, pawn_push(Side::White, "e2", "e4"))
+            .unwrap();
+        let e5 = tree
+            .add_move(e4, pawn_push(Side::Black, "e7", "e5"))
+            .unwrap();
+        let c5 = tree
+            .add_move(e4, pawn_push(Side::Black, "c7", "c5"))
+            .unwrap();
+        let knight = Piece {
+            ptype: PieceType::Knight,
+            side: Side::White,
+        };
+        let nf3 = tree
+            .add_move(c5, Move::new(knight, "g1", "f3", MoveType::Quiet).unwrap())
+            .unwrap();
+        let e6 = tree
+            .add_move(e4, pawn_push(Side::Black, "e7", "e6"))
+            .unwrap();
+        let d4 = tree
+            .add_move(e5, pawn_push(Side::White, "d2", "d4"))
+            .unwrap();
+
+        (tree, [e4, e5, c5, nf3, e6, d4])
+    }
+
+    #[test]
+    fn test_add_move() {
+        let (mut tree, [e4, e5, c5, _, e6, d4]) = sample_tree();
+
+        assert_eq!(6, tree.len());
+        assert_eq!(vec![e4, e5, d4], tree.mainline(tree.root()));
+        assert_eq!(&[e5, c5, e6], tree.children(e4));
+        assert_eq!(Some(e4), tree.parent(c5));
+        assert!(tree.is_mainline_child(e5));
+        assert!(!tree.is_mainline_child(c5));
+        assert_eq!(
+            Ok(e5),
+            tree.add_move(e4, pawn_push(Side::Black, "e7", "e5"))
+        );
+        assert_eq!(6, tree.len());
+    }
+
+    #[test]
+    fn test_promote_and_demote() {
+        let (mut tree, [e4, e5, c5, _, e6, _]) = sample_tree();
+
+        tree.promote(c5).unwrap();
+        assert_eq!(&[c5, e5, e6], tree.children(e4));
+
+        tree.demote(c5).unwrap();
+        tree.demote(e6).unwrap();
+        assert_eq!(&[e5, c5, e6], tree.children(e4));
+
+        tree.promote_to_mainline(e6).unwrap();
+        assert_eq!(&[e6, e5, c5], tree.children(e4));
+        assert!(tree.promote(tree.root()).is_err());
+    }
+
+    #[test]
+    fn test_remove() {
+        let (mut tree, [e4, e5, c5, nf3, e6, _]) = sample_tree();
+
+        tree.remove(c5).unwrap();
+
+        assert_eq!(4, tree.len());
+        assert_eq!(&[e5, e6], tree.children(e4));
+        assert_eq!(None, tree.played(nf3));
+        assert!(tree.remove(c5).is_err());
+        assert!(tree.remove(tree.root()).is_err());
+    }
+
+    #[test]
+    fn test_annotate() {
+        let (mut tree, [e4, _, c5, _, _, _]) = sample_tree();
+
+        tree.annotate(e4, Annotation::Good).unwrap();
+        tree.annotate(e4, Nag(146)).unwrap();
+        tree.annotate(e4, Nag(1)).unwrap();
+
+        assert_eq!(&[Nag(1), Nag(146)], tree.nags(e4));
+        assert!(tree.nags(c5).is_empty());
+
+        tree.clear_nags(e4).unwrap();
+        tree.remove(c5).unwrap();
+
+        assert!(tree.nags(e4).is_empty());
+        assert!(tree.annotate(c5, Annotation::Dubious).is_err());
+    }
+
+    #[test]
+    fn test_depth_first() {
+        let (tree, [e4, e5, c5, nf3, e6, d4]) = sample_tree();
+
+        assert_eq!(
+            vec![e4, e5, d4, c5, nf3, e6],
+            tree.depth_first(tree.root()).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![nf3], tree.depth_first(c5).collect::<Vec<_>>());
+    }
+}
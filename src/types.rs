@@ -9,6 +9,35 @@ pub enum Side {
     Black,
 }
 
+impl Side {
+    /// Both sides, white first
+    pub const ALL: [Side; 2] = [Side::White, Side::Black];
+
+    /// The other side
+    pub fn opposite(&self) -> Side {
+        match self {
+            Side::White => Side::Black,
+            Side::Black => Side::White,
+        }
+    }
+
+    /// Index of the side in per-side tables, white first
+    pub fn index(&self) -> usize {
+        match self {
+            Side::White => 0,
+            Side::Black => 1,
+        }
+    }
+
+    /// Rank index as seen from this side, 0 being its back rank
+    pub fn relative_rank(&self, rank_index: u8) -> u8 {
+        match self {
+            Side::White => rank_index,
+            Side::Black => 7 - rank_index,
+        }
+    }
+}
+
 /// Piece types
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum PieceType {
@@ -20,12 +49,100 @@ pub enum PieceType {
     King,
 }
 
+impl PieceType {
+    /// All piece types, from pawn to king
+    pub const ALL: [PieceType; 6] = [
+        PieceType::Pawn,
+        PieceType::Knight,
+        PieceType::Bishop,
+        PieceType::Rook,
+        PieceType::Queen,
+        PieceType::King,
+    ];
+
+    /// Index of the piece type in `PieceType::ALL`
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Piece {
     pub ptype: PieceType,
     pub side: Side,
 }
 
+impl Piece {
+    /// Maps a FEN piece letter to a piece
+    ///
+    /// Uppercase letters are white pieces, lowercase letters are black pieces
+    pub fn from_fen_char(letter: char) -> Result<Piece, InvalidPieceError> {
+        let ptype = match letter.to_ascii_lowercase() {
+            'p' => PieceType::Pawn,
+            'n' => PieceType::Knight,
+            'b' => PieceType::Bishop,
+            'r' => PieceType::Rook,
+            'q' => PieceType::Queen,
+            'k' => PieceType::King,
+            _ => {
+                return Err(InvalidPieceError {
+                    msg: letter.to_string(),
+                })
+            }
+        };
+        let side = if letter.is_ascii_uppercase() {
+            Side::White
+        } else {
+            Side::Black
+        };
+
+        Ok(Piece { ptype, side })
+    }
+
+    /// Maps a piece to its FEN letter
+    ///
+    /// Inverse of `from_fen_char`
+    pub fn to_fen_char(&self) -> char {
+        let letter = match self.ptype {
+            PieceType::Pawn => 'p',
+            PieceType::Knight => 'n',
+            PieceType::Bishop => 'b',
+            PieceType::Rook => 'r',
+            PieceType::Queen => 'q',
+            PieceType::King => 'k',
+        };
+
+        match self.side {
+            Side::White => letter.to_ascii_uppercase(),
+            Side::Black => letter,
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Side {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(*u.choose(&Side::ALL)?)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for PieceType {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(*u.choose(&PieceType::ALL)?)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Piece {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Piece {
+            ptype: u.arbitrary()?,
+            side: u.arbitrary()?,
+        })
+    }
+}
+
 impl fmt::Display for Piece {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.side {
@@ -102,9 +219,15 @@ impl TryFrom<&str> for Piece {
                 side: Side::Black,
                 ptype: PieceType::King,
             }),
-            _ => Err(InvalidPieceError {
-                msg: String::from(piece),
-            }),
+            _ => {
+                let mut chars = piece.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(letter), None) => Piece::from_fen_char(letter),
+                    _ => Err(InvalidPieceError {
+                        msg: String::from(piece),
+                    }),
+                }
+            }
         }
     }
 }
@@ -139,6 +262,20 @@ impl fmt::Display for InvalidSquareError {
     }
 }
 
+/// Error type for board diagram parse errors
+#[derive(Debug)]
+pub struct InvalidDiagramError {
+    pub msg: String,
+}
+
+impl Error for InvalidDiagramError {}
+
+impl fmt::Display for InvalidDiagramError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid diagram: {}", self.msg)
+    }
+}
+
 /// Error type for file parse errors
 #[derive(Debug, PartialEq)]
 pub struct InvalidFileError {
@@ -153,6 +290,48 @@ impl fmt::Display for InvalidFileError {
     }
 }
 
+/// Error type for game tree nodes that are missing or cannot be changed
+#[derive(Debug, PartialEq)]
+pub struct InvalidNodeError {
+    pub msg: String,
+}
+
+impl Error for InvalidNodeError {}
+
+impl fmt::Display for InvalidNodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid node: {}", self.msg)
+    }
+}
+
+/// Error type for NAG and annotation symbol parse errors
+#[derive(Debug, PartialEq)]
+pub struct InvalidAnnotationError {
+    pub msg: String,
+}
+
+impl Error for InvalidAnnotationError {}
+
+impl fmt::Display for InvalidAnnotationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid annotation: {}", self.msg)
+    }
+}
+
+/// Error type for impossible castling king and rook files
+#[derive(Debug, PartialEq)]
+pub struct InvalidCastlingError {
+    pub msg: String,
+}
+
+impl Error for InvalidCastlingError {}
+
+impl fmt::Display for InvalidCastlingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid castling: {}", self.msg)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,6 +345,51 @@ mod tests {
             },
             Piece::try_from("♕").unwrap()
         );
+        assert_eq!(
+            Piece {
+                side: Side::Black,
+                ptype: PieceType::Knight
+            },
+            Piece::try_from("n").unwrap()
+        );
         assert!(Piece::try_from("bad input").is_err());
+        assert!(Piece::try_from("x").is_err());
+    }
+
+    #[test]
+    fn test_piece_fen_chars() {
+        for &side in Side::ALL.iter() {
+            for &ptype in PieceType::ALL.iter() {
+                let piece = Piece { side, ptype };
+                assert_eq!(piece, Piece::from_fen_char(piece.to_fen_char()).unwrap());
+            }
+        }
+        assert_eq!(
+            'Q',
+            Piece {
+                side: Side::White,
+                ptype: PieceType::Queen
+            }
+            .to_fen_char()
+        );
+        assert!(Piece::from_fen_char('x').is_err());
+    }
+
+    #[test]
+    fn test_side_opposite() {
+        assert_eq!(Side::Black, Side::White.opposite());
+        assert_eq!(Side::White, Side::Black.opposite());
+    }
+
+    #[test]
+    fn test_indices() {
+        assert_eq!(0, Side::White.index());
+        assert_eq!(1, Side::Black.index());
+        assert_eq!(0, Side::White.relative_rank(0));
+        assert_eq!(0, Side::Black.relative_rank(7));
+        assert_eq!(5, Side::Black.relative_rank(2));
+        for (index, ptype) in PieceType::ALL.iter().enumerate() {
+            assert_eq!(index, ptype.index());
+        }
     }
 }